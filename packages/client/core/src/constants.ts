@@ -3,3 +3,9 @@ import { PublicKey } from "@solana/web3.js";
 export const CRYPTID_PROGRAM = new PublicKey(
   "cryptJTh61jY5kbUmBEXyc86tBUyueBDrLuNSZWmUcs"
 );
+
+// The global registry of middleware programs that can approve cryptid transactions
+export const APPROVAL_REGISTRY = PublicKey.findProgramAddressSync(
+  [Buffer.from("approval_registry")],
+  CRYPTID_PROGRAM
+)[0];
@@ -10,7 +10,7 @@ export {
   CreateOptions,
 } from "./api/cryptidClient";
 export { MiddlewareRegistry } from "./service/middlewareRegistry";
-export { CRYPTID_PROGRAM } from "./constants";
+export { APPROVAL_REGISTRY, CRYPTID_PROGRAM } from "./constants";
 
 export const util = {
   getCryptidAccountAddress,
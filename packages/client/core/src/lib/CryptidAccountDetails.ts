@@ -66,7 +66,14 @@ export class CryptidAccountDetails {
       didAccount[0],
       didAccount[1],
       middlewareAccounts.map(
-        ([key, accountInfo]) => new Middleware(accountInfo.owner, key)
+        ([key, accountInfo]) =>
+          new Middleware(
+            accountInfo.owner,
+            key,
+            cryptidAccount.superuserMiddleware.some((superuser) =>
+              superuser.equals(key)
+            )
+          )
       )
     );
   }
@@ -40,7 +40,10 @@ export class CryptidTransaction {
     readonly authority: PublicKey,
     readonly instructions: InstructionData[],
     readonly accountMetas: AccountMeta[],
-    readonly controllerChainReferences: ControllerAccountReference[]
+    readonly controllerChainReferences: ControllerAccountReference[],
+    // The payer of the transaction account, which receives the rent when it is executed or closed.
+    // A transaction account proposed by this authority is paid for by it.
+    readonly payer: PublicKey = authority
   ) {}
 
   get accountMetasOnlyKeys(): AccountMeta[] {
@@ -192,7 +195,8 @@ export class CryptidTransaction {
       authority,
       instructions,
       allRemainingAccountMetas,
-      controllerIndices
+      controllerIndices,
+      transactionAccount.payer
     );
   }
 
@@ -278,6 +282,8 @@ export class CryptidTransaction {
           this.cryptidAccount.didAccountBump,
           TransactionState.toBorsh(state),
           allowUnauthorized,
+          null, // no expiry
+          0, // no flags
           this.instructions,
           this.accountMetas.length
         )
@@ -351,7 +357,7 @@ export class CryptidTransaction {
         didProgram: DID_SOL_PROGRAM,
         did: this.cryptidAccount.didAccount,
         authority: this.authority,
-        destination: this.authority,
+        destination: this.payer,
         transactionAccount: transactionAccountAddress,
       })
       .remainingAccounts(this.accountMetas);
@@ -378,7 +384,7 @@ export class CryptidTransaction {
         didProgram: DID_SOL_PROGRAM,
         did: this.cryptidAccount.didAccount,
         authority: this.authority,
-        destination: this.authority,
+        destination: this.payer,
         transactionAccount: transactionAccountAddress,
       })
       .remainingAccounts(this.accountMetasOnlyKeys);
@@ -73,23 +73,25 @@ export class CryptidService {
     address: PublicKey,
     cryptidAccount: CryptidAccount
   ): Promise<CryptidAccountDetails> {
-    const middlewareAccount =
-      cryptidAccount.middleware &&
-      (await this.program.provider.connection.getAccountInfo(
-        cryptidAccount.middleware
-      ));
-
-    // TODO support chaining
-    const middleware: [PublicKey, AccountInfo<Buffer>] | undefined =
-      middlewareAccount && cryptidAccount.middleware
-        ? [cryptidAccount.middleware, middlewareAccount]
-        : undefined;
+    const middlewareAccounts =
+      await this.program.provider.connection.getMultipleAccountsInfo(
+        cryptidAccount.middlewares
+      );
+
+    const middlewares = cryptidAccount.middlewares
+      .map(
+        (address, index): [PublicKey, AccountInfo<Buffer> | null] => [
+          address,
+          middlewareAccounts[index],
+        ]
+      )
+      .filter((m): m is [PublicKey, AccountInfo<Buffer>] => m[1] !== null);
 
     return CryptidAccountDetails.fromAccounts(
       did,
       address,
       cryptidAccount,
-      middleware ? [middleware] : []
+      middlewares
     );
   }
 
@@ -147,17 +149,13 @@ export class CryptidService {
   }
 
   public async createAccount(details: CryptidAccountDetails): Promise<string> {
-    const lastMiddleware =
-      details.middlewares.length > 0
-        ? details.middlewares[details.middlewares.length - 1]
-        : undefined;
     const superuserMiddlewares = details.middlewares
       .filter((m) => m.isSuperuser)
       .map((m) => m.address);
     return (
       this.program.methods
         .createCryptidAccount(
-          lastMiddleware?.address || null,
+          details.middlewares.map((m) => m.address),
           superuserMiddlewares,
           // Pass in the controller dids (if any)
           this.controllerChainPubkeys.map((c) => c[1]),
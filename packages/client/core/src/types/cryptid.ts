@@ -6,6 +6,8 @@ export enum TransactionState {
   NotReady,
   Ready,
   Executed,
+  // Only set by the program while executing a non-atomic transaction in chunks
+  Executing,
 }
 // use namespace to add toBorsh to the above enum
 // eslint-disable-next-line @typescript-eslint/no-namespace
@@ -87,6 +87,22 @@ export type CheckDid = {
             "The gateway token for the transaction",
             "Must be owned by the owner of the transaction"
           ]
+        },
+        {
+          "name": "approvalRegistry",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The registry of middleware programs that can approve cryptid transactions"
+          ]
+        },
+        {
+          "name": "cryptidAccount",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The cryptid account the transaction was proposed for"
+          ]
         }
       ],
       "args": []
@@ -333,6 +349,22 @@ export const IDL: CheckDid = {
             "The gateway token for the transaction",
             "Must be owned by the owner of the transaction"
           ]
+        },
+        {
+          "name": "approvalRegistry",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The registry of middleware programs that can approve cryptid transactions"
+          ]
+        },
+        {
+          "name": "cryptidAccount",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The cryptid account the transaction was proposed for"
+          ]
         }
       ],
       "args": []
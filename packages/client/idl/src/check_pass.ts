@@ -101,6 +101,22 @@ export type CheckPass = {
           "isMut": false,
           "isSigner": false
         },
+        {
+          "name": "approvalRegistry",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The registry of middleware programs that can approve cryptid transactions"
+          ]
+        },
+        {
+          "name": "cryptidAccount",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The cryptid account the transaction was proposed for"
+          ]
+        },
         {
           "name": "gatewayProgram",
           "isMut": false,
@@ -288,6 +304,22 @@ export const IDL: CheckPass = {
           "isMut": false,
           "isSigner": false
         },
+        {
+          "name": "approvalRegistry",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The registry of middleware programs that can approve cryptid transactions"
+          ]
+        },
+        {
+          "name": "cryptidAccount",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The cryptid account the transaction was proposed for"
+          ]
+        },
         {
           "name": "gatewayProgram",
           "isMut": false,
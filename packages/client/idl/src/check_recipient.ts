@@ -55,6 +55,22 @@ export type CheckRecipient = {
           "name": "cryptidProgram",
           "isMut": false,
           "isSigner": false
+        },
+        {
+          "name": "approvalRegistry",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The registry of middleware programs that can approve cryptid transactions"
+          ]
+        },
+        {
+          "name": "cryptidAccount",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The cryptid account the transaction was proposed for"
+          ]
         }
       ],
       "args": []
@@ -172,6 +188,22 @@ export const IDL: CheckRecipient = {
           "name": "cryptidProgram",
           "isMut": false,
           "isSigner": false
+        },
+        {
+          "name": "approvalRegistry",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The registry of middleware programs that can approve cryptid transactions"
+          ]
+        },
+        {
+          "name": "cryptidAccount",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The cryptid account the transaction was proposed for"
+          ]
         }
       ],
       "args": []
@@ -42,9 +42,9 @@ export type Cryptid = {
       ],
       "args": [
         {
-          "name": "middleware",
+          "name": "middlewares",
           "type": {
-            "option": "publicKey"
+            "vec": "publicKey"
           }
         },
         {
@@ -69,6 +69,89 @@ export type Cryptid = {
         }
       ]
     },
+    {
+      "name": "updateCryptidAccount",
+      "accounts": [
+        {
+          "name": "cryptidAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "didProgram",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The program for the DID"
+          ]
+        },
+        {
+          "name": "did",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The DID on the Cryptid instance"
+          ]
+        },
+        {
+          "name": "authority",
+          "isMut": true,
+          "isSigner": true,
+          "docs": [
+            "The signer of the transaction. Must be a DID authority."
+          ]
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "middlewares",
+          "type": {
+            "vec": "publicKey"
+          }
+        },
+        {
+          "name": "addSuperuserMiddlewares",
+          "type": {
+            "vec": "publicKey"
+          }
+        },
+        {
+          "name": "removeSuperuserMiddlewares",
+          "type": {
+            "vec": "publicKey"
+          }
+        },
+        {
+          "name": "threshold",
+          "type": "u8"
+        },
+        {
+          "name": "transactionReclaimAge",
+          "type": {
+            "option": "u64"
+          }
+        },
+        {
+          "name": "controllerChain",
+          "type": {
+            "vec": "publicKey"
+          }
+        },
+        {
+          "name": "index",
+          "type": "u32"
+        },
+        {
+          "name": "didAccountBump",
+          "type": "u8"
+        }
+      ]
+    },
     {
       "name": "directExecute",
       "accounts": [
@@ -145,7 +228,7 @@ export type Cryptid = {
       "accounts": [
         {
           "name": "cryptidAccount",
-          "isMut": false,
+          "isMut": true,
           "isSigner": false,
           "docs": [
             "The Cryptid instance that can execute the transaction."
@@ -214,6 +297,122 @@ export type Cryptid = {
           "name": "allowUnauthorized",
           "type": "bool"
         },
+        {
+          "name": "expiry",
+          "type": {
+            "option": {
+              "defined": "TransactionExpiry"
+            }
+          }
+        },
+        {
+          "name": "flags",
+          "type": "u8"
+        },
+        {
+          "name": "instructions",
+          "type": {
+            "vec": {
+              "defined": "AbbreviatedInstructionData"
+            }
+          }
+        },
+        {
+          "name": "numAccounts",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "proposeSequencedTransaction",
+      "accounts": [
+        {
+          "name": "cryptidAccount",
+          "isMut": true,
+          "isSigner": false,
+          "docs": [
+            "The Cryptid instance that can execute the transaction."
+          ]
+        },
+        {
+          "name": "did",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The did account owner of the Cryptid instance"
+          ]
+        },
+        {
+          "name": "didProgram",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The program for the DID"
+          ]
+        },
+        {
+          "name": "authority",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "transactionAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "controllerChain",
+          "type": {
+            "vec": {
+              "defined": "DIDReference"
+            }
+          }
+        },
+        {
+          "name": "cryptidAccountBump",
+          "type": "u8"
+        },
+        {
+          "name": "cryptidAccountIndex",
+          "type": "u32"
+        },
+        {
+          "name": "didAccountBump",
+          "type": "u8"
+        },
+        {
+          "name": "sequence",
+          "type": "u64"
+        },
+        {
+          "name": "state",
+          "type": {
+            "defined": "TransactionState"
+          }
+        },
+        {
+          "name": "allowUnauthorized",
+          "type": "bool"
+        },
+        {
+          "name": "expiry",
+          "type": {
+            "option": {
+              "defined": "TransactionExpiry"
+            }
+          }
+        },
+        {
+          "name": "flags",
+          "type": "u8"
+        },
         {
           "name": "instructions",
           "type": {
@@ -389,7 +588,7 @@ export type Cryptid = {
       ]
     },
     {
-      "name": "closeTransaction",
+      "name": "executeTransactionChunk",
       "accounts": [
         {
           "name": "cryptidAccount",
@@ -433,7 +632,8 @@ export type Cryptid = {
           "isMut": true,
           "isSigner": false,
           "docs": [
-            "The instruction to execute"
+            "The transaction to execute.",
+            "Closed once its last instruction has executed"
           ]
         }
       ],
@@ -457,418 +657,2014 @@ export type Cryptid = {
         {
           "name": "didAccountBump",
           "type": "u8"
+        },
+        {
+          "name": "flags",
+          "type": "u8"
+        },
+        {
+          "name": "count",
+          "type": "u8"
         }
       ]
     },
     {
-      "name": "approveExecution",
+      "name": "approveTransaction",
       "accounts": [
         {
-          "name": "middlewareAccount",
+          "name": "cryptidAccount",
           "isMut": false,
-          "isSigner": true
+          "isSigner": false,
+          "docs": [
+            "The Cryptid instance that can execute the transaction."
+          ]
         },
         {
-          "name": "transactionAccount",
-          "isMut": true,
-          "isSigner": false
-        }
-      ],
-      "args": []
-    },
-    {
-      "name": "superuserApproveExecution",
-      "accounts": [
+          "name": "did",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The DID on the Cryptid instance"
+          ]
+        },
         {
-          "name": "middlewareAccount",
+          "name": "didProgram",
           "isMut": false,
-          "isSigner": true
+          "isSigner": false,
+          "docs": [
+            "The program for the DID"
+          ]
+        },
+        {
+          "name": "authority",
+          "isMut": true,
+          "isSigner": true,
+          "docs": [
+            "The DID authority approving the transaction"
+          ]
         },
         {
           "name": "transactionAccount",
           "isMut": true,
-          "isSigner": false
+          "isSigner": false,
+          "docs": [
+            "The transaction to approve"
+          ]
         },
         {
-          "name": "cryptidAccount",
+          "name": "systemProgram",
           "isMut": false,
           "isSigner": false
         }
       ],
-      "args": []
-    }
-  ],
-  "accounts": [
-    {
-      "name": "cryptidAccount",
-      "docs": [
-        "The data for an on-chain Cryptid Account"
-      ],
-      "type": {
-        "kind": "struct",
-        "fields": [
-          {
-            "name": "middleware",
-            "docs": [
+      "args": [
+        {
+          "name": "controllerChain",
+          "type": {
+            "vec": {
+              "defined": "DIDReference"
+            }
+          }
+        },
+        {
+          "name": "cryptidAccountBump",
+          "type": "u8"
+        },
+        {
+          "name": "cryptidAccountIndex",
+          "type": "u32"
+        },
+        {
+          "name": "didAccountBump",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "closeTransaction",
+      "accounts": [
+        {
+          "name": "cryptidAccount",
+          "isMut": true,
+          "isSigner": false,
+          "docs": [
+            "The Cryptid instance to execute with"
+          ]
+        },
+        {
+          "name": "did",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The DID on the Cryptid instance"
+          ]
+        },
+        {
+          "name": "didProgram",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The program for the DID"
+          ]
+        },
+        {
+          "name": "authority",
+          "isMut": false,
+          "isSigner": true,
+          "docs": [
+            "The signer of the transaction"
+          ]
+        },
+        {
+          "name": "destination",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "transactionAccount",
+          "isMut": true,
+          "isSigner": false,
+          "docs": [
+            "The instruction to execute"
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "controllerChain",
+          "type": {
+            "vec": {
+              "defined": "DIDReference"
+            }
+          }
+        },
+        {
+          "name": "cryptidAccountBump",
+          "type": "u8"
+        },
+        {
+          "name": "cryptidAccountIndex",
+          "type": "u32"
+        },
+        {
+          "name": "didAccountBump",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "closeCryptidAccount",
+      "accounts": [
+        {
+          "name": "cryptidAccount",
+          "isMut": true,
+          "isSigner": false,
+          "docs": [
+            "The Cryptid instance to close"
+          ]
+        },
+        {
+          "name": "did",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The DID on the Cryptid instance"
+          ]
+        },
+        {
+          "name": "didProgram",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The program for the DID"
+          ]
+        },
+        {
+          "name": "authority",
+          "isMut": false,
+          "isSigner": true,
+          "docs": [
+            "The signer of the transaction. Must be a DID authority."
+          ]
+        },
+        {
+          "name": "destination",
+          "isMut": true,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "controllerChain",
+          "type": {
+            "vec": {
+              "defined": "DIDReference"
+            }
+          }
+        },
+        {
+          "name": "cryptidAccountBump",
+          "type": "u8"
+        },
+        {
+          "name": "cryptidAccountIndex",
+          "type": "u32"
+        },
+        {
+          "name": "didAccountBump",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "migrateCryptidAccount",
+      "accounts": [
+        {
+          "name": "cryptidAccount",
+          "isMut": true,
+          "isSigner": false,
+          "docs": [
+            "The cryptid account to migrate, in the legacy layout"
+          ]
+        },
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true,
+          "docs": [
+            "Pays for any additional rent required by the migrated account"
+          ]
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "reclaimTransaction",
+      "accounts": [
+        {
+          "name": "cryptidAccount",
+          "isMut": true,
+          "isSigner": false,
+          "docs": [
+            "The Cryptid instance the transaction was proposed for"
+          ]
+        },
+        {
+          "name": "payer",
+          "isMut": true,
+          "isSigner": false,
+          "docs": [
+            "The account that paid the rent for the transaction account, which receives it back"
+          ]
+        },
+        {
+          "name": "transactionAccount",
+          "isMut": true,
+          "isSigner": false,
+          "docs": [
+            "The transaction account to reclaim"
+          ]
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "approveExecution",
+      "accounts": [
+        {
+          "name": "middlewareAccount",
+          "isMut": false,
+          "isSigner": true,
+          "docs": [
+            "The middleware account approving the transaction.",
+            "Must be owned by a program in the approval registry, and be in the middleware pipeline of the cryptid account"
+          ]
+        },
+        {
+          "name": "transactionAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "approvalRegistry",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The registry of middleware programs that can approve transactions"
+          ]
+        },
+        {
+          "name": "cryptidAccount",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The cryptid account that the transaction was proposed for"
+          ]
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "superuserApproveExecution",
+      "accounts": [
+        {
+          "name": "middlewareAccount",
+          "isMut": false,
+          "isSigner": true,
+          "docs": [
+            "The middleware account approving the transaction.",
+            "Must be owned by a program in the approval registry"
+          ]
+        },
+        {
+          "name": "transactionAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "approvalRegistry",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The registry of middleware programs that can approve transactions"
+          ]
+        },
+        {
+          "name": "cryptidAccount",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "initializeApprovalRegistry",
+      "accounts": [
+        {
+          "name": "approvalRegistry",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "authority",
+          "isMut": true,
+          "isSigner": true,
+          "docs": [
+            "The upgrade authority of the cryptid program"
+          ]
+        },
+        {
+          "name": "cryptidProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "programData",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "middlewarePrograms",
+          "type": {
+            "vec": "publicKey"
+          }
+        },
+        {
+          "name": "registryAuthority",
+          "type": "publicKey"
+        }
+      ]
+    },
+    {
+      "name": "updateApprovalRegistry",
+      "accounts": [
+        {
+          "name": "approvalRegistry",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "authority",
+          "isMut": true,
+          "isSigner": true,
+          "docs": [
+            "The authority of the registry"
+          ]
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "addMiddlewarePrograms",
+          "type": {
+            "vec": "publicKey"
+          }
+        },
+        {
+          "name": "removeMiddlewarePrograms",
+          "type": {
+            "vec": "publicKey"
+          }
+        }
+      ]
+    }
+  ],
+  "accounts": [
+    {
+      "name": "approvalRegistry",
+      "docs": [
+        "The global registry of middleware programs trusted to approve transactions.",
+        "Governed by an authority chosen by the upgrade authority of the cryptid program when the registry is initialized."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "authority",
+            "docs": [
+              "The authority that can update the registry"
+            ],
+            "type": "publicKey"
+          },
+          {
+            "name": "middlewarePrograms",
+            "docs": [
+              "The programs whose accounts may approve transactions as middleware"
+            ],
+            "type": {
+              "vec": "publicKey"
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "cryptidAccount",
+      "docs": [
+        "The data for an on-chain Cryptid Account"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "middlewares",
+            "docs": [
+              "The middleware pipeline of this cryptid account, in the order in which the middlewares approve transactions"
+            ],
+            "type": {
+              "vec": "publicKey"
+            }
+          },
+          {
+            "name": "index",
+            "docs": [
+              "The index of this cryptid account - allows multiple cryptid accounts per DID"
+            ],
+            "type": "u32"
+          },
+          {
+            "name": "superuserMiddleware",
+            "docs": [
+              "Middlewares that have \"Superuser\" status on the cryptid account"
+            ],
+            "type": {
+              "vec": "publicKey"
+            }
+          },
+          {
+            "name": "threshold",
+            "docs": [
+              "The number of distinct DID authorities that must sign to execute a transaction"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "transactionReclaimAge",
+            "docs": [
+              "The number of slots after which an open transaction account can be reclaimed by anyone,",
+              "refunding the rent to its payer. If None, transaction accounts can only be reclaimed once expired."
+            ],
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "openTransactions",
+            "docs": [
+              "The number of transaction accounts proposed for this cryptid account that have not yet been",
+              "executed or closed"
+            ],
+            "type": "u32"
+          },
+          {
+            "name": "nextTransactionSequence",
+            "docs": [
+              "The sequence number of the next transaction account proposed with `propose_sequenced_transaction`"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "nextExecutableSequence",
+            "docs": [
+              "The sequence number of the next sequenced transaction that can be executed or closed.",
+              "Sequenced transactions are executed (or closed) in the order in which they were proposed.",
+              "An expired transaction can be closed ahead of its turn, skipping any earlier ones."
+            ],
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "transactionAccount",
+      "docs": [
+        "A proposed transaction stored on-chain, in preparation to be executed"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "cryptidAccount",
+            "docs": [
+              "The cryptid account for the transaction"
+            ],
+            "type": "publicKey"
+          },
+          {
+            "name": "did",
+            "docs": [
+              "The owner of the cryptid account (Typically a DID account)"
+            ],
+            "type": "publicKey"
+          },
+          {
+            "name": "accounts",
+            "docs": [
+              "The accounts `instructions` references (excluding the cryptid account)"
+            ],
+            "type": {
+              "vec": "publicKey"
+            }
+          },
+          {
+            "name": "instructions",
+            "docs": [
+              "The instructions that will be executed"
+            ],
+            "type": {
+              "vec": {
+                "defined": "AbbreviatedInstructionData"
+              }
+            }
+          },
+          {
+            "name": "approvedMiddlewares",
+            "docs": [
+              "The middleware PDAs that have approved the transaction.",
+              "Space is reserved for these when the transaction is proposed, see [`crate::state::cryptid_account::CryptidAccount::max_middleware_approvals`].",
+              "Approvals beyond the reserved space are rejected"
+            ],
+            "type": {
+              "vec": "publicKey"
+            }
+          },
+          {
+            "name": "slot",
+            "docs": [
+              "The slot in which the transaction was proposed"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "expiry",
+            "docs": [
+              "The point after which the transaction can no longer be approved or executed, if any"
+            ],
+            "type": {
+              "option": {
+                "defined": "TransactionExpiry"
+              }
+            }
+          },
+          {
+            "name": "state",
+            "docs": [
+              "The transaction state, to prevent replay attacks",
+              "in case an executed transaction account is not immediately",
+              "garbage-collected by the runtime"
+            ],
+            "type": {
+              "defined": "TransactionState"
+            }
+          },
+          {
+            "name": "unauthorizedSigner",
+            "docs": [
+              "If the transaction account is proposed by an authority on the DID, (the standard case)",
+              "then this is set to None.",
+              "If the transaction account is proposed by an unauthorized cryptid client, then",
+              "it is set to to that signer, and only a `superUser` middleware can approve it."
+            ],
+            "type": {
+              "option": "publicKey"
+            }
+          },
+          {
+            "name": "authorized",
+            "type": "bool"
+          },
+          {
+            "name": "approvers",
+            "docs": [
+              "The DID authorities that have approved the transaction, counting towards the cryptid account threshold",
+              "Cleared if the transaction is extended"
+            ],
+            "type": {
+              "vec": "publicKey"
+            }
+          },
+          {
+            "name": "payer",
+            "docs": [
+              "The account that paid the rent for the transaction account when it was proposed"
+            ],
+            "type": "publicKey"
+          },
+          {
+            "name": "flags",
+            "docs": [
+              "The [`TransactionFlags`] the transaction was proposed with"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "sequence",
+            "docs": [
+              "The position of the transaction in the cryptid account's sequence,",
+              "if it was proposed with `propose_sequenced_transaction`"
+            ],
+            "type": {
+              "option": "u64"
+            }
+          }
+        ]
+      }
+    }
+  ],
+  "types": [
+    {
+      "name": "AbbreviatedAccountMeta",
+      "docs": [
+        "An account for an instruction, similar to Solana's [`AccountMeta`](AccountMeta)"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "key",
+            "docs": [
+              "The key of the account"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "meta",
+            "docs": [
+              "Information about the account"
+            ],
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "AbbreviatedInstructionData",
+      "docs": [
+        "The data about an instruction to be executed. Similar to Solana's [`Instruction`](SolanaInstruction).",
+        "Accounts are stored as indices in AbbreviatedAccountMeta to save space"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "programId",
+            "docs": [
+              "The program to execute"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "accounts",
+            "docs": [
+              "The accounts to send to the program"
+            ],
+            "type": {
+              "vec": {
+                "defined": "AbbreviatedAccountMeta"
+              }
+            }
+          },
+          {
+            "name": "data",
+            "docs": [
+              "The data for the instruction"
+            ],
+            "type": "bytes"
+          }
+        ]
+      }
+    },
+    {
+      "name": "LegacyCryptidAccount",
+      "docs": [
+        "The layout of cryptid accounts created before middleware pipelines, thresholds and transaction tracking",
+        "were added to [`CryptidAccount`]. These can no longer be deserialized as a [`CryptidAccount`],",
+        "and are upgraded with `migrate_cryptid_account`."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "middleware",
+            "docs": [
               "The middleware, if any, used by this cryptid account"
             ],
-            "type": {
-              "option": "publicKey"
+            "type": {
+              "option": "publicKey"
+            }
+          },
+          {
+            "name": "index",
+            "docs": [
+              "The index of this cryptid account"
+            ],
+            "type": "u32"
+          },
+          {
+            "name": "superuserMiddleware",
+            "docs": [
+              "Middlewares that have \"Superuser\" status on the cryptid account"
+            ],
+            "type": {
+              "vec": "publicKey"
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "DIDReference",
+      "docs": [
+        "A struct that represents a DID, eg for use in a controller chain in a cryptid instruction",
+        "The account index is the index of the did account in the all_accounts array.",
+        "The DID may be generative or non-generative.",
+        "In the generative case, the did_account provides no information, as it is empty and owned",
+        "by the system program.",
+        "Therefore, the authority key is also needed in order to derive the did account with the correct identifier etc."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "accountIndex",
+            "docs": [
+              "The index in the all_accounts array for the DID Account"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "authorityKey",
+            "docs": [
+              "The did authority key (did:sol:<authority_key>)."
+            ],
+            "type": "publicKey"
+          }
+        ]
+      }
+    },
+    {
+      "name": "InstructionSize",
+      "docs": [
+        "A helper struct for calculating [`InstructionData`] size"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "accounts",
+            "docs": [
+              "The number of accounts in the instruction"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "dataLen",
+            "docs": [
+              "The size of the instruction data"
+            ],
+            "type": "u16"
+          }
+        ]
+      }
+    },
+    {
+      "name": "TransactionExpiry",
+      "docs": [
+        "The point after which a proposed [`TransactionAccount`] can no longer be approved or executed"
+      ],
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "Slot",
+            "fields": [
+              "u64"
+            ]
+          },
+          {
+            "name": "UnixTimestamp",
+            "fields": [
+              "i64"
+            ]
+          }
+        ]
+      }
+    },
+    {
+      "name": "TransactionState",
+      "docs": [
+        "A [`TransactionAccount`]'s state"
+      ],
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "NotReady"
+          },
+          {
+            "name": "Ready"
+          },
+          {
+            "name": "Executed"
+          },
+          {
+            "name": "Executing",
+            "fields": [
+              {
+                "name": "next_index",
+                "docs": [
+                  "The index of the next instruction to execute"
+                ],
+                "type": "u16"
+              }
+            ]
+          }
+        ]
+      }
+    }
+  ],
+  "events": [
+    {
+      "name": "CryptidAccountCreated",
+      "fields": [
+        {
+          "name": "cryptidAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "did",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "authority",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "index",
+          "type": "u32",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "CryptidAccountUpdated",
+      "fields": [
+        {
+          "name": "cryptidAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "did",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "authority",
+          "type": "publicKey",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "CryptidAccountClosed",
+      "fields": [
+        {
+          "name": "cryptidAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "did",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "authority",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "destination",
+          "type": "publicKey",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "TransactionProposed",
+      "fields": [
+        {
+          "name": "cryptidAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "did",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transactionAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "authority",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "state",
+          "type": {
+            "defined": "TransactionState"
+          },
+          "index": false
+        },
+        {
+          "name": "sequence",
+          "type": {
+            "option": "u64"
+          },
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "TransactionExtended",
+      "fields": [
+        {
+          "name": "cryptidAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "did",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transactionAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "authority",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "instructionCount",
+          "type": "u16",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "TransactionStateChanged",
+      "fields": [
+        {
+          "name": "cryptidAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "did",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transactionAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "authority",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "previousState",
+          "type": {
+            "defined": "TransactionState"
+          },
+          "index": false
+        },
+        {
+          "name": "state",
+          "type": {
+            "defined": "TransactionState"
+          },
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "TransactionApproved",
+      "fields": [
+        {
+          "name": "cryptidAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "did",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transactionAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "authority",
+          "type": "publicKey",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "MiddlewareApproved",
+      "fields": [
+        {
+          "name": "cryptidAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "did",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transactionAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "middlewareAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "middlewareProgram",
+          "type": "publicKey",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "SuperuserApproved",
+      "fields": [
+        {
+          "name": "cryptidAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "did",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transactionAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "middlewareAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "middlewareProgram",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "authorized",
+          "type": "bool",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "TransactionExecuted",
+      "fields": [
+        {
+          "name": "cryptidAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "did",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transactionAccount",
+          "type": {
+            "option": "publicKey"
+          },
+          "index": false
+        },
+        {
+          "name": "authority",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "programIds",
+          "type": {
+            "vec": "publicKey"
+          },
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "TransactionClosed",
+      "fields": [
+        {
+          "name": "cryptidAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "did",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transactionAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "authority",
+          "type": {
+            "option": "publicKey"
+          },
+          "index": false
+        },
+        {
+          "name": "destination",
+          "type": "publicKey",
+          "index": false
+        }
+      ]
+    }
+  ],
+  "errors": [
+    {
+      "code": 6000,
+      "name": "NotEnoughSigners",
+      "msg": "Not enough signers"
+    },
+    {
+      "code": 6001,
+      "name": "WrongDID",
+      "msg": "Wrong DID"
+    },
+    {
+      "code": 6002,
+      "name": "WrongDIDProgram",
+      "msg": "Wrong DID program"
+    },
+    {
+      "code": 6003,
+      "name": "WrongCryptidAccount",
+      "msg": "A wrong Cryptid account was passed"
+    },
+    {
+      "code": 6004,
+      "name": "SubInstructionError",
+      "msg": "Error in sub-instruction"
+    },
+    {
+      "code": 6005,
+      "name": "InvalidTransactionState",
+      "msg": "Invalid transaction state"
+    },
+    {
+      "code": 6006,
+      "name": "UnauthorizedTransaction",
+      "msg": "The transaction was proposed by a non-authority on the DID and has not been authorized by a superuser middleware."
+    },
+    {
+      "code": 6007,
+      "name": "AccountMismatch",
+      "msg": "An account in the transaction accounts did not match what was expected"
+    },
+    {
+      "code": 6008,
+      "name": "KeyMustBeSigner",
+      "msg": "Signer is not authorised to sign for this Cryptid account"
+    },
+    {
+      "code": 6009,
+      "name": "CreatingWithZeroIndex",
+      "msg": "Attempt to create a Cryptid account with index zero, reserved for the default account."
+    },
+    {
+      "code": 6010,
+      "name": "IndexOutOfRange",
+      "msg": "Index out of range."
+    },
+    {
+      "code": 6011,
+      "name": "NoAccountFromSeeds",
+      "msg": "No account from seeds."
+    },
+    {
+      "code": 6012,
+      "name": "AccountNotFromSeeds",
+      "msg": "Account not from seeds."
+    },
+    {
+      "code": 6013,
+      "name": "IncorrectMiddleware",
+      "msg": "The expected middleware did not approve the transaction."
+    },
+    {
+      "code": 6014,
+      "name": "IncorrectSuperuserMiddleware",
+      "msg": "The middleware is not registered as a superuser middleware on the cryptid account."
+    },
+    {
+      "code": 6015,
+      "name": "InvalidAccounts",
+      "msg": "The accounts passed to execute do not match those in the transaction account."
+    },
+    {
+      "code": 6016,
+      "name": "InvalidMiddlewareAccount",
+      "msg": "Approve Execution needs to be called with a valid Middleware Account."
+    },
+    {
+      "code": 6017,
+      "name": "AlreadyAuthorizedTransactionAccount",
+      "msg": "Transaction Account is already authorized and cannot be authorized again."
+    },
+    {
+      "code": 6018,
+      "name": "OpenTransactionAccounts",
+      "msg": "The cryptid account cannot be closed while it has open transaction accounts."
+    },
+    {
+      "code": 6019,
+      "name": "DirectExecuteWithMiddleware",
+      "msg": "Cryptid accounts with middleware cannot use direct execution. Propose the transaction instead."
+    },
+    {
+      "code": 6020,
+      "name": "InvalidThreshold",
+      "msg": "The threshold of a cryptid account must be at least one."
+    },
+    {
+      "code": 6021,
+      "name": "AlreadyApproved",
+      "msg": "The authority has already approved the transaction."
+    },
+    {
+      "code": 6022,
+      "name": "TransactionExpired",
+      "msg": "The transaction has expired."
+    },
+    {
+      "code": 6023,
+      "name": "TransactionNotReclaimable",
+      "msg": "The transaction account has not expired or been open long enough to be reclaimed."
+    },
+    {
+      "code": 6024,
+      "name": "InvalidRentDestination",
+      "msg": "The rent for the transaction account can only be refunded to its payer."
+    },
+    {
+      "code": 6025,
+      "name": "SequenceOnGenerativeAccount",
+      "msg": "Sequenced transactions can only be proposed for non-generative cryptid accounts."
+    },
+    {
+      "code": 6026,
+      "name": "InvalidTransactionSequence",
+      "msg": "The transaction sequence number does not match the cryptid account."
+    },
+    {
+      "code": 6027,
+      "name": "InvalidAccountMeta",
+      "msg": "The account meta contains unknown flags."
+    },
+    {
+      "code": 6028,
+      "name": "MissingInstructionAccount",
+      "msg": "An instruction references an account that was not provided."
+    },
+    {
+      "code": 6029,
+      "name": "InvalidAccountCount",
+      "msg": "The number of accounts passed to execute does not match those in the transaction account."
+    },
+    {
+      "code": 6030,
+      "name": "AtomicTransaction",
+      "msg": "Only transactions proposed as non-atomic can be executed in chunks."
+    },
+    {
+      "code": 6031,
+      "name": "InvalidChunkSize",
+      "msg": "A chunk must execute at least one instruction."
+    },
+    {
+      "code": 6032,
+      "name": "AccountAlreadyInUse",
+      "msg": "The account to be created is already in use."
+    },
+    {
+      "code": 6033,
+      "name": "CryptidAccountNotRentExempt",
+      "msg": "The cryptid account must remain rent-exempt."
+    },
+    {
+      "code": 6034,
+      "name": "CryptidAccountModified",
+      "msg": "The cryptid account was modified by a sub-instruction."
+    },
+    {
+      "code": 6035,
+      "name": "ReentrantInstruction",
+      "msg": "Instructions executed by cryptid cannot invoke the cryptid program."
+    },
+    {
+      "code": 6036,
+      "name": "UnsatisfiableSigner",
+      "msg": "An instruction requires a signature from an account that cannot sign."
+    },
+    {
+      "code": 6037,
+      "name": "UnsatisfiableWritable",
+      "msg": "An instruction requires an account that cannot be written to be writable."
+    },
+    {
+      "code": 6038,
+      "name": "NotUpgradeAuthority",
+      "msg": "The signer is not the upgrade authority of the cryptid program."
+    },
+    {
+      "code": 6039,
+      "name": "NotRegistryAuthority",
+      "msg": "The signer is not the authority of the approval registry."
+    },
+    {
+      "code": 6040,
+      "name": "UnregisteredMiddleware",
+      "msg": "The middleware account is not owned by a registered middleware program."
+    },
+    {
+      "code": 6041,
+      "name": "DuplicateMiddleware",
+      "msg": "A middleware cannot appear more than once in the middleware pipeline."
+    },
+    {
+      "code": 6042,
+      "name": "MiddlewareNotInPipeline",
+      "msg": "The middleware is not in the middleware pipeline of the cryptid account."
+    },
+    {
+      "code": 6043,
+      "name": "TooManyInstructions",
+      "msg": "A non-atomic transaction cannot hold this many instructions."
+    },
+    {
+      "code": 6044,
+      "name": "InvalidExecuteFlags",
+      "msg": "Unknown execute flags."
+    },
+    {
+      "code": 6045,
+      "name": "OutOfOrderTransaction",
+      "msg": "Sequenced transactions must be executed or closed in the order in which they were proposed."
+    },
+    {
+      "code": 6046,
+      "name": "NoSpaceForMiddlewareApproval",
+      "msg": "The transaction account has no space for another middleware approval. Propose the transaction again."
+    },
+    {
+      "code": 6047,
+      "name": "NotLegacyCryptidAccount",
+      "msg": "The cryptid account is not in the legacy layout."
+    },
+    {
+      "code": 6048,
+      "name": "ImmutableCryptidProgram",
+      "msg": "The cryptid program is immutable, so the approval registry can no longer be initialized."
+    },
+    {
+      "code": 6049,
+      "name": "MiddlewareApprovalRequired",
+      "msg": "The middleware of the cryptid account must approve changes to it."
+    },
+    {
+      "code": 6050,
+      "name": "UnauthorizedSequencedTransaction",
+      "msg": "Sequenced transactions can only be proposed by an authority on the DID."
+    }
+  ]
+};
+
+export const IDL: Cryptid = {
+  "version": "0.1.0",
+  "name": "cryptid",
+  "instructions": [
+    {
+      "name": "createCryptidAccount",
+      "accounts": [
+        {
+          "name": "cryptidAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "didProgram",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The program for the DID"
+          ]
+        },
+        {
+          "name": "did",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The DID on the Cryptid instance"
+          ]
+        },
+        {
+          "name": "authority",
+          "isMut": true,
+          "isSigner": true,
+          "docs": [
+            "The signer of the transaction. Must be a DID authority."
+          ]
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "middlewares",
+          "type": {
+            "vec": "publicKey"
+          }
+        },
+        {
+          "name": "superuserMiddlewares",
+          "type": {
+            "vec": "publicKey"
+          }
+        },
+        {
+          "name": "controllerChain",
+          "type": {
+            "vec": "publicKey"
+          }
+        },
+        {
+          "name": "index",
+          "type": "u32"
+        },
+        {
+          "name": "didAccountBump",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "updateCryptidAccount",
+      "accounts": [
+        {
+          "name": "cryptidAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "didProgram",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The program for the DID"
+          ]
+        },
+        {
+          "name": "did",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The DID on the Cryptid instance"
+          ]
+        },
+        {
+          "name": "authority",
+          "isMut": true,
+          "isSigner": true,
+          "docs": [
+            "The signer of the transaction. Must be a DID authority."
+          ]
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "middlewares",
+          "type": {
+            "vec": "publicKey"
+          }
+        },
+        {
+          "name": "addSuperuserMiddlewares",
+          "type": {
+            "vec": "publicKey"
+          }
+        },
+        {
+          "name": "removeSuperuserMiddlewares",
+          "type": {
+            "vec": "publicKey"
+          }
+        },
+        {
+          "name": "threshold",
+          "type": "u8"
+        },
+        {
+          "name": "transactionReclaimAge",
+          "type": {
+            "option": "u64"
+          }
+        },
+        {
+          "name": "controllerChain",
+          "type": {
+            "vec": "publicKey"
+          }
+        },
+        {
+          "name": "index",
+          "type": "u32"
+        },
+        {
+          "name": "didAccountBump",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "directExecute",
+      "accounts": [
+        {
+          "name": "cryptidAccount",
+          "isMut": true,
+          "isSigner": false,
+          "docs": [
+            "The Cryptid instance to execute with"
+          ]
+        },
+        {
+          "name": "did",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The DID on the Cryptid instance"
+          ]
+        },
+        {
+          "name": "didProgram",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The program for the DID"
+          ]
+        },
+        {
+          "name": "authority",
+          "isMut": false,
+          "isSigner": true,
+          "docs": [
+            "The signer of the transaction"
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "controllerChain",
+          "type": {
+            "vec": {
+              "defined": "DIDReference"
             }
-          },
-          {
-            "name": "index",
-            "docs": [
-              "The index of this cryptid account - allows multiple cryptid accounts per DID"
-            ],
-            "type": "u32"
-          },
-          {
-            "name": "superuserMiddleware",
-            "docs": [
-              "Middlewares that have \"Superuser\" status on the cryptid account"
-            ],
-            "type": {
-              "vec": "publicKey"
+          }
+        },
+        {
+          "name": "instructions",
+          "type": {
+            "vec": {
+              "defined": "AbbreviatedInstructionData"
+            }
+          }
+        },
+        {
+          "name": "cryptidAccountBump",
+          "type": "u8"
+        },
+        {
+          "name": "cryptidAccountIndex",
+          "type": "u32"
+        },
+        {
+          "name": "didAccountBump",
+          "type": "u8"
+        },
+        {
+          "name": "flags",
+          "type": "u8"
+        }
+      ]
+    },
+    {
+      "name": "proposeTransaction",
+      "accounts": [
+        {
+          "name": "cryptidAccount",
+          "isMut": true,
+          "isSigner": false,
+          "docs": [
+            "The Cryptid instance that can execute the transaction."
+          ]
+        },
+        {
+          "name": "did",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The did account owner of the Cryptid instance"
+          ]
+        },
+        {
+          "name": "didProgram",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The program for the DID"
+          ]
+        },
+        {
+          "name": "authority",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "transactionAccount",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": [
+        {
+          "name": "controllerChain",
+          "type": {
+            "vec": {
+              "defined": "DIDReference"
+            }
+          }
+        },
+        {
+          "name": "cryptidAccountBump",
+          "type": "u8"
+        },
+        {
+          "name": "cryptidAccountIndex",
+          "type": "u32"
+        },
+        {
+          "name": "didAccountBump",
+          "type": "u8"
+        },
+        {
+          "name": "state",
+          "type": {
+            "defined": "TransactionState"
+          }
+        },
+        {
+          "name": "allowUnauthorized",
+          "type": "bool"
+        },
+        {
+          "name": "expiry",
+          "type": {
+            "option": {
+              "defined": "TransactionExpiry"
+            }
+          }
+        },
+        {
+          "name": "flags",
+          "type": "u8"
+        },
+        {
+          "name": "instructions",
+          "type": {
+            "vec": {
+              "defined": "AbbreviatedInstructionData"
             }
           }
-        ]
-      }
+        },
+        {
+          "name": "numAccounts",
+          "type": "u8"
+        }
+      ]
     },
     {
-      "name": "transactionAccount",
-      "docs": [
-        "A proposed transaction stored on-chain, in preparation to be executed"
+      "name": "proposeSequencedTransaction",
+      "accounts": [
+        {
+          "name": "cryptidAccount",
+          "isMut": true,
+          "isSigner": false,
+          "docs": [
+            "The Cryptid instance that can execute the transaction."
+          ]
+        },
+        {
+          "name": "did",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The did account owner of the Cryptid instance"
+          ]
+        },
+        {
+          "name": "didProgram",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The program for the DID"
+          ]
+        },
+        {
+          "name": "authority",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "transactionAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
       ],
-      "type": {
-        "kind": "struct",
-        "fields": [
-          {
-            "name": "cryptidAccount",
-            "docs": [
-              "The cryptid account for the transaction"
-            ],
-            "type": "publicKey"
-          },
-          {
-            "name": "did",
-            "docs": [
-              "The owner of the cryptid account (Typically a DID account)"
-            ],
-            "type": "publicKey"
-          },
-          {
-            "name": "accounts",
-            "docs": [
-              "The accounts `instructions` references (excluding the cryptid account)"
-            ],
-            "type": {
-              "vec": "publicKey"
-            }
-          },
-          {
-            "name": "instructions",
-            "docs": [
-              "The instructions that will be executed"
-            ],
-            "type": {
-              "vec": {
-                "defined": "AbbreviatedInstructionData"
-              }
-            }
-          },
-          {
-            "name": "approvedMiddleware",
-            "docs": [
-              "The most recent middleware PDA that approved the transaction"
-            ],
-            "type": {
-              "option": "publicKey"
-            }
-          },
-          {
-            "name": "slot",
-            "docs": [
-              "The slot in which the transaction was proposed",
-              "This is used to prevent replay attacks"
-            ],
-            "type": "u8"
-          },
-          {
-            "name": "state",
-            "docs": [
-              "The transaction state, to prevent replay attacks",
-              "in case an executed transaction account is not immediately",
-              "garbage-collected by the runtime"
-            ],
-            "type": {
-              "defined": "TransactionState"
-            }
-          },
-          {
-            "name": "unauthorizedSigner",
-            "docs": [
-              "If the transaction account is proposed by an authority on the DID, (the standard case)",
-              "then this is set to None.",
-              "If the transaction account is proposed by an unauthorized cryptid client, then",
-              "it is set to to that signer, and only a `superUser` middleware can approve it."
-            ],
-            "type": {
-              "option": "publicKey"
+      "args": [
+        {
+          "name": "controllerChain",
+          "type": {
+            "vec": {
+              "defined": "DIDReference"
             }
-          },
-          {
-            "name": "authorized",
-            "type": "bool"
           }
-        ]
-      }
-    }
-  ],
-  "types": [
-    {
-      "name": "AbbreviatedAccountMeta",
-      "docs": [
-        "An account for an instruction, similar to Solana's [`AccountMeta`](AccountMeta)"
-      ],
-      "type": {
-        "kind": "struct",
-        "fields": [
-          {
-            "name": "key",
-            "docs": [
-              "The key of the account"
-            ],
-            "type": "u8"
-          },
-          {
-            "name": "meta",
-            "docs": [
-              "Information about the account"
-            ],
-            "type": "u8"
+        },
+        {
+          "name": "cryptidAccountBump",
+          "type": "u8"
+        },
+        {
+          "name": "cryptidAccountIndex",
+          "type": "u32"
+        },
+        {
+          "name": "didAccountBump",
+          "type": "u8"
+        },
+        {
+          "name": "sequence",
+          "type": "u64"
+        },
+        {
+          "name": "state",
+          "type": {
+            "defined": "TransactionState"
           }
-        ]
-      }
-    },
-    {
-      "name": "AbbreviatedInstructionData",
-      "docs": [
-        "The data about an instruction to be executed. Similar to Solana's [`Instruction`](SolanaInstruction).",
-        "Accounts are stored as indices in AbbreviatedAccountMeta to save space"
-      ],
-      "type": {
-        "kind": "struct",
-        "fields": [
-          {
-            "name": "programId",
-            "docs": [
-              "The program to execute"
-            ],
-            "type": "u8"
-          },
-          {
-            "name": "accounts",
-            "docs": [
-              "The accounts to send to the program"
-            ],
-            "type": {
-              "vec": {
-                "defined": "AbbreviatedAccountMeta"
-              }
+        },
+        {
+          "name": "allowUnauthorized",
+          "type": "bool"
+        },
+        {
+          "name": "expiry",
+          "type": {
+            "option": {
+              "defined": "TransactionExpiry"
             }
-          },
-          {
-            "name": "data",
-            "docs": [
-              "The data for the instruction"
-            ],
-            "type": "bytes"
           }
-        ]
-      }
-    },
-    {
-      "name": "DIDReference",
-      "docs": [
-        "A struct that represents a DID, eg for use in a controller chain in a cryptid instruction",
-        "The account index is the index of the did account in the all_accounts array.",
-        "The DID may be generative or non-generative.",
-        "In the generative case, the did_account provides no information, as it is empty and owned",
-        "by the system program.",
-        "Therefore, the authority key is also needed in order to derive the did account with the correct identifier etc."
-      ],
-      "type": {
-        "kind": "struct",
-        "fields": [
-          {
-            "name": "accountIndex",
-            "docs": [
-              "The index in the all_accounts array for the DID Account"
-            ],
-            "type": "u8"
-          },
-          {
-            "name": "authorityKey",
-            "docs": [
-              "The did authority key (did:sol:<authority_key>)."
-            ],
-            "type": "publicKey"
+        },
+        {
+          "name": "flags",
+          "type": "u8"
+        },
+        {
+          "name": "instructions",
+          "type": {
+            "vec": {
+              "defined": "AbbreviatedInstructionData"
+            }
           }
-        ]
-      }
+        },
+        {
+          "name": "numAccounts",
+          "type": "u8"
+        }
+      ]
     },
     {
-      "name": "InstructionSize",
-      "docs": [
-        "A helper struct for calculating [`InstructionData`] size"
+      "name": "extendTransaction",
+      "accounts": [
+        {
+          "name": "cryptidAccount",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The Cryptid instance that can execute the transaction."
+          ]
+        },
+        {
+          "name": "did",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The did account owner of the Cryptid instance"
+          ]
+        },
+        {
+          "name": "didProgram",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The program for the DID"
+          ]
+        },
+        {
+          "name": "authority",
+          "isMut": true,
+          "isSigner": true
+        },
+        {
+          "name": "transactionAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
+        }
       ],
-      "type": {
-        "kind": "struct",
-        "fields": [
-          {
-            "name": "accounts",
-            "docs": [
-              "The number of accounts in the instruction"
-            ],
-            "type": "u8"
-          },
-          {
-            "name": "dataLen",
-            "docs": [
-              "The size of the instruction data"
-            ],
-            "type": "u16"
+      "args": [
+        {
+          "name": "controllerChain",
+          "type": {
+            "vec": {
+              "defined": "DIDReference"
+            }
           }
-        ]
-      }
-    },
-    {
-      "name": "TransactionState",
-      "docs": [
-        "A [`TransactionAccount`]'s state"
-      ],
-      "type": {
-        "kind": "enum",
-        "variants": [
-          {
-            "name": "NotReady"
-          },
-          {
-            "name": "Ready"
-          },
-          {
-            "name": "Executed"
+        },
+        {
+          "name": "cryptidAccountBump",
+          "type": "u8"
+        },
+        {
+          "name": "cryptidAccountIndex",
+          "type": "u32"
+        },
+        {
+          "name": "didAccountBump",
+          "type": "u8"
+        },
+        {
+          "name": "state",
+          "type": {
+            "defined": "TransactionState"
           }
-        ]
-      }
-    }
-  ],
-  "errors": [
-    {
-      "code": 6000,
-      "name": "NotEnoughSigners",
-      "msg": "Not enough signers"
-    },
-    {
-      "code": 6001,
-      "name": "WrongDID",
-      "msg": "Wrong DID"
-    },
-    {
-      "code": 6002,
-      "name": "WrongDIDProgram",
-      "msg": "Wrong DID program"
-    },
-    {
-      "code": 6003,
-      "name": "WrongCryptidAccount",
-      "msg": "A wrong Cryptid account was passed"
-    },
-    {
-      "code": 6004,
-      "name": "SubInstructionError",
-      "msg": "Error in sub-instruction"
-    },
-    {
-      "code": 6005,
-      "name": "InvalidTransactionState",
-      "msg": "Invalid transaction state"
-    },
-    {
-      "code": 6006,
-      "name": "UnauthorizedTransaction",
-      "msg": "The transaction was proposed by a non-authority on the DID and has not been authorized by a superuser middleware."
-    },
-    {
-      "code": 6007,
-      "name": "AccountMismatch",
-      "msg": "An account in the transaction accounts did not match what was expected"
-    },
-    {
-      "code": 6008,
-      "name": "KeyMustBeSigner",
-      "msg": "Signer is not authorised to sign for this Cryptid account"
-    },
-    {
-      "code": 6009,
-      "name": "CreatingWithZeroIndex",
-      "msg": "Attempt to create a Cryptid account with index zero, reserved for the default account."
-    },
-    {
-      "code": 6010,
-      "name": "IndexOutOfRange",
-      "msg": "Index out of range."
-    },
-    {
-      "code": 6011,
-      "name": "NoAccountFromSeeds",
-      "msg": "No account from seeds."
-    },
-    {
-      "code": 6012,
-      "name": "AccountNotFromSeeds",
-      "msg": "Account not from seeds."
-    },
-    {
-      "code": 6013,
-      "name": "IncorrectMiddleware",
-      "msg": "The expected middleware did not approve the transaction."
-    },
-    {
-      "code": 6014,
-      "name": "IncorrectSuperuserMiddleware",
-      "msg": "The middleware is not registered as a superuser middleware on the cryptid account."
-    },
-    {
-      "code": 6015,
-      "name": "InvalidAccounts",
-      "msg": "The accounts passed to execute do not match those in the transaction account."
-    },
-    {
-      "code": 6016,
-      "name": "InvalidMiddlewareAccount",
-      "msg": "Approve Execution needs to be called with a valid Middleware Account."
+        },
+        {
+          "name": "instructions",
+          "type": {
+            "vec": {
+              "defined": "AbbreviatedInstructionData"
+            }
+          }
+        },
+        {
+          "name": "numAccounts",
+          "type": "u8"
+        }
+      ]
     },
     {
-      "code": 6017,
-      "name": "AlreadyAuthorizedTransactionAccount",
-      "msg": "Already authorized Transaction Account."
-    }
-  ]
-};
-
-export const IDL: Cryptid = {
-  "version": "0.1.0",
-  "name": "cryptid",
-  "instructions": [
-    {
-      "name": "createCryptidAccount",
+      "name": "executeTransaction",
       "accounts": [
         {
           "name": "cryptidAccount",
           "isMut": true,
-          "isSigner": false
+          "isSigner": false,
+          "docs": [
+            "The Cryptid instance to execute with"
+          ]
+        },
+        {
+          "name": "did",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The DID on the Cryptid instance"
+          ]
         },
         {
           "name": "didProgram",
@@ -879,58 +2675,56 @@ export const IDL: Cryptid = {
           ]
         },
         {
-          "name": "did",
+          "name": "authority",
           "isMut": false,
-          "isSigner": false,
+          "isSigner": true,
           "docs": [
-            "The DID on the Cryptid instance"
+            "The signer of the transaction"
           ]
         },
         {
-          "name": "authority",
+          "name": "destination",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "transactionAccount",
           "isMut": true,
-          "isSigner": true,
+          "isSigner": false,
           "docs": [
-            "The signer of the transaction. Must be a DID authority."
+            "The instruction to execute"
           ]
-        },
-        {
-          "name": "systemProgram",
-          "isMut": false,
-          "isSigner": false
         }
       ],
       "args": [
         {
-          "name": "middleware",
-          "type": {
-            "option": "publicKey"
-          }
-        },
-        {
-          "name": "superuserMiddlewares",
+          "name": "controllerChain",
           "type": {
-            "vec": "publicKey"
+            "vec": {
+              "defined": "DIDReference"
+            }
           }
         },
         {
-          "name": "controllerChain",
-          "type": {
-            "vec": "publicKey"
-          }
+          "name": "cryptidAccountBump",
+          "type": "u8"
         },
         {
-          "name": "index",
+          "name": "cryptidAccountIndex",
           "type": "u32"
         },
         {
           "name": "didAccountBump",
           "type": "u8"
+        },
+        {
+          "name": "flags",
+          "type": "u8"
         }
       ]
     },
     {
-      "name": "directExecute",
+      "name": "executeTransactionChunk",
       "accounts": [
         {
           "name": "cryptidAccount",
@@ -963,6 +2757,20 @@ export const IDL: Cryptid = {
           "docs": [
             "The signer of the transaction"
           ]
+        },
+        {
+          "name": "destination",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "transactionAccount",
+          "isMut": true,
+          "isSigner": false,
+          "docs": [
+            "The transaction to execute.",
+            "Closed once its last instruction has executed"
+          ]
         }
       ],
       "args": [
@@ -974,14 +2782,6 @@ export const IDL: Cryptid = {
             }
           }
         },
-        {
-          "name": "instructions",
-          "type": {
-            "vec": {
-              "defined": "AbbreviatedInstructionData"
-            }
-          }
-        },
         {
           "name": "cryptidAccountBump",
           "type": "u8"
@@ -997,11 +2797,15 @@ export const IDL: Cryptid = {
         {
           "name": "flags",
           "type": "u8"
+        },
+        {
+          "name": "count",
+          "type": "u8"
         }
       ]
     },
     {
-      "name": "proposeTransaction",
+      "name": "approveTransaction",
       "accounts": [
         {
           "name": "cryptidAccount",
@@ -1016,7 +2820,7 @@ export const IDL: Cryptid = {
           "isMut": false,
           "isSigner": false,
           "docs": [
-            "The did account owner of the Cryptid instance"
+            "The DID on the Cryptid instance"
           ]
         },
         {
@@ -1030,12 +2834,18 @@ export const IDL: Cryptid = {
         {
           "name": "authority",
           "isMut": true,
-          "isSigner": true
+          "isSigner": true,
+          "docs": [
+            "The DID authority approving the transaction"
+          ]
         },
         {
           "name": "transactionAccount",
           "isMut": true,
-          "isSigner": true
+          "isSigner": false,
+          "docs": [
+            "The transaction to approve"
+          ]
         },
         {
           "name": "systemProgram",
@@ -1063,40 +2873,18 @@ export const IDL: Cryptid = {
         {
           "name": "didAccountBump",
           "type": "u8"
-        },
-        {
-          "name": "state",
-          "type": {
-            "defined": "TransactionState"
-          }
-        },
-        {
-          "name": "allowUnauthorized",
-          "type": "bool"
-        },
-        {
-          "name": "instructions",
-          "type": {
-            "vec": {
-              "defined": "AbbreviatedInstructionData"
-            }
-          }
-        },
-        {
-          "name": "numAccounts",
-          "type": "u8"
         }
       ]
     },
     {
-      "name": "extendTransaction",
+      "name": "closeTransaction",
       "accounts": [
         {
           "name": "cryptidAccount",
-          "isMut": false,
+          "isMut": true,
           "isSigner": false,
           "docs": [
-            "The Cryptid instance that can execute the transaction."
+            "The Cryptid instance to execute with"
           ]
         },
         {
@@ -1104,7 +2892,7 @@ export const IDL: Cryptid = {
           "isMut": false,
           "isSigner": false,
           "docs": [
-            "The did account owner of the Cryptid instance"
+            "The DID on the Cryptid instance"
           ]
         },
         {
@@ -1117,18 +2905,24 @@ export const IDL: Cryptid = {
         },
         {
           "name": "authority",
-          "isMut": true,
-          "isSigner": true
+          "isMut": false,
+          "isSigner": true,
+          "docs": [
+            "The signer of the transaction"
+          ]
         },
         {
-          "name": "transactionAccount",
+          "name": "destination",
           "isMut": true,
           "isSigner": false
         },
         {
-          "name": "systemProgram",
-          "isMut": false,
-          "isSigner": false
+          "name": "transactionAccount",
+          "isMut": true,
+          "isSigner": false,
+          "docs": [
+            "The instruction to execute"
+          ]
         }
       ],
       "args": [
@@ -1151,36 +2945,18 @@ export const IDL: Cryptid = {
         {
           "name": "didAccountBump",
           "type": "u8"
-        },
-        {
-          "name": "state",
-          "type": {
-            "defined": "TransactionState"
-          }
-        },
-        {
-          "name": "instructions",
-          "type": {
-            "vec": {
-              "defined": "AbbreviatedInstructionData"
-            }
-          }
-        },
-        {
-          "name": "numAccounts",
-          "type": "u8"
         }
       ]
     },
     {
-      "name": "executeTransaction",
+      "name": "closeCryptidAccount",
       "accounts": [
         {
           "name": "cryptidAccount",
           "isMut": true,
           "isSigner": false,
           "docs": [
-            "The Cryptid instance to execute with"
+            "The Cryptid instance to close"
           ]
         },
         {
@@ -1204,21 +2980,13 @@ export const IDL: Cryptid = {
           "isMut": false,
           "isSigner": true,
           "docs": [
-            "The signer of the transaction"
+            "The signer of the transaction. Must be a DID authority."
           ]
         },
         {
           "name": "destination",
           "isMut": true,
           "isSigner": false
-        },
-        {
-          "name": "transactionAccount",
-          "isMut": true,
-          "isSigner": false,
-          "docs": [
-            "The instruction to execute"
-          ]
         }
       ],
       "args": [
@@ -1241,124 +3009,247 @@ export const IDL: Cryptid = {
         {
           "name": "didAccountBump",
           "type": "u8"
-        },
-        {
-          "name": "flags",
-          "type": "u8"
         }
       ]
     },
     {
-      "name": "closeTransaction",
+      "name": "migrateCryptidAccount",
       "accounts": [
         {
           "name": "cryptidAccount",
           "isMut": true,
           "isSigner": false,
           "docs": [
-            "The Cryptid instance to execute with"
+            "The cryptid account to migrate, in the legacy layout"
           ]
         },
         {
-          "name": "did",
+          "name": "payer",
+          "isMut": true,
+          "isSigner": true,
+          "docs": [
+            "Pays for any additional rent required by the migrated account"
+          ]
+        },
+        {
+          "name": "systemProgram",
           "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "reclaimTransaction",
+      "accounts": [
+        {
+          "name": "cryptidAccount",
+          "isMut": true,
           "isSigner": false,
           "docs": [
-            "The DID on the Cryptid instance"
+            "The Cryptid instance the transaction was proposed for"
           ]
         },
         {
-          "name": "didProgram",
+          "name": "payer",
+          "isMut": true,
+          "isSigner": false,
+          "docs": [
+            "The account that paid the rent for the transaction account, which receives it back"
+          ]
+        },
+        {
+          "name": "transactionAccount",
+          "isMut": true,
+          "isSigner": false,
+          "docs": [
+            "The transaction account to reclaim"
+          ]
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "approveExecution",
+      "accounts": [
+        {
+          "name": "middlewareAccount",
+          "isMut": false,
+          "isSigner": true,
+          "docs": [
+            "The middleware account approving the transaction.",
+            "Must be owned by a program in the approval registry, and be in the middleware pipeline of the cryptid account"
+          ]
+        },
+        {
+          "name": "transactionAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "approvalRegistry",
           "isMut": false,
           "isSigner": false,
           "docs": [
-            "The program for the DID"
+            "The registry of middleware programs that can approve transactions"
           ]
         },
         {
-          "name": "authority",
+          "name": "cryptidAccount",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The cryptid account that the transaction was proposed for"
+          ]
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "superuserApproveExecution",
+      "accounts": [
+        {
+          "name": "middlewareAccount",
           "isMut": false,
           "isSigner": true,
           "docs": [
-            "The signer of the transaction"
+            "The middleware account approving the transaction.",
+            "Must be owned by a program in the approval registry"
           ]
         },
         {
-          "name": "destination",
+          "name": "transactionAccount",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "approvalRegistry",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The registry of middleware programs that can approve transactions"
+          ]
+        },
+        {
+          "name": "cryptidAccount",
+          "isMut": false,
+          "isSigner": false
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "initializeApprovalRegistry",
+      "accounts": [
+        {
+          "name": "approvalRegistry",
+          "isMut": true,
+          "isSigner": false
+        },
+        {
+          "name": "authority",
           "isMut": true,
+          "isSigner": true,
+          "docs": [
+            "The upgrade authority of the cryptid program"
+          ]
+        },
+        {
+          "name": "cryptidProgram",
+          "isMut": false,
+          "isSigner": false
+        },
+        {
+          "name": "programData",
+          "isMut": false,
           "isSigner": false
         },
         {
-          "name": "transactionAccount",
-          "isMut": true,
-          "isSigner": false,
-          "docs": [
-            "The instruction to execute"
-          ]
+          "name": "systemProgram",
+          "isMut": false,
+          "isSigner": false
         }
       ],
       "args": [
         {
-          "name": "controllerChain",
+          "name": "middlewarePrograms",
           "type": {
-            "vec": {
-              "defined": "DIDReference"
-            }
+            "vec": "publicKey"
           }
         },
         {
-          "name": "cryptidAccountBump",
-          "type": "u8"
-        },
-        {
-          "name": "cryptidAccountIndex",
-          "type": "u32"
-        },
-        {
-          "name": "didAccountBump",
-          "type": "u8"
+          "name": "registryAuthority",
+          "type": "publicKey"
         }
       ]
     },
     {
-      "name": "approveExecution",
+      "name": "updateApprovalRegistry",
       "accounts": [
         {
-          "name": "middlewareAccount",
-          "isMut": false,
-          "isSigner": true
-        },
-        {
-          "name": "transactionAccount",
+          "name": "approvalRegistry",
           "isMut": true,
           "isSigner": false
-        }
-      ],
-      "args": []
-    },
-    {
-      "name": "superuserApproveExecution",
-      "accounts": [
-        {
-          "name": "middlewareAccount",
-          "isMut": false,
-          "isSigner": true
         },
         {
-          "name": "transactionAccount",
+          "name": "authority",
           "isMut": true,
-          "isSigner": false
+          "isSigner": true,
+          "docs": [
+            "The authority of the registry"
+          ]
         },
         {
-          "name": "cryptidAccount",
+          "name": "systemProgram",
           "isMut": false,
           "isSigner": false
         }
       ],
-      "args": []
+      "args": [
+        {
+          "name": "addMiddlewarePrograms",
+          "type": {
+            "vec": "publicKey"
+          }
+        },
+        {
+          "name": "removeMiddlewarePrograms",
+          "type": {
+            "vec": "publicKey"
+          }
+        }
+      ]
     }
   ],
   "accounts": [
+    {
+      "name": "approvalRegistry",
+      "docs": [
+        "The global registry of middleware programs trusted to approve transactions.",
+        "Governed by an authority chosen by the upgrade authority of the cryptid program when the registry is initialized."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "authority",
+            "docs": [
+              "The authority that can update the registry"
+            ],
+            "type": "publicKey"
+          },
+          {
+            "name": "middlewarePrograms",
+            "docs": [
+              "The programs whose accounts may approve transactions as middleware"
+            ],
+            "type": {
+              "vec": "publicKey"
+            }
+          }
+        ]
+      }
+    },
     {
       "name": "cryptidAccount",
       "docs": [
@@ -1368,12 +3259,12 @@ export const IDL: Cryptid = {
         "kind": "struct",
         "fields": [
           {
-            "name": "middleware",
+            "name": "middlewares",
             "docs": [
-              "The middleware, if any, used by this cryptid account"
+              "The middleware pipeline of this cryptid account, in the order in which the middlewares approve transactions"
             ],
             "type": {
-              "option": "publicKey"
+              "vec": "publicKey"
             }
           },
           {
@@ -1381,247 +3272,743 @@ export const IDL: Cryptid = {
             "docs": [
               "The index of this cryptid account - allows multiple cryptid accounts per DID"
             ],
-            "type": "u32"
+            "type": "u32"
+          },
+          {
+            "name": "superuserMiddleware",
+            "docs": [
+              "Middlewares that have \"Superuser\" status on the cryptid account"
+            ],
+            "type": {
+              "vec": "publicKey"
+            }
+          },
+          {
+            "name": "threshold",
+            "docs": [
+              "The number of distinct DID authorities that must sign to execute a transaction"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "transactionReclaimAge",
+            "docs": [
+              "The number of slots after which an open transaction account can be reclaimed by anyone,",
+              "refunding the rent to its payer. If None, transaction accounts can only be reclaimed once expired."
+            ],
+            "type": {
+              "option": "u64"
+            }
+          },
+          {
+            "name": "openTransactions",
+            "docs": [
+              "The number of transaction accounts proposed for this cryptid account that have not yet been",
+              "executed or closed"
+            ],
+            "type": "u32"
+          },
+          {
+            "name": "nextTransactionSequence",
+            "docs": [
+              "The sequence number of the next transaction account proposed with `propose_sequenced_transaction`"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "nextExecutableSequence",
+            "docs": [
+              "The sequence number of the next sequenced transaction that can be executed or closed.",
+              "Sequenced transactions are executed (or closed) in the order in which they were proposed.",
+              "An expired transaction can be closed ahead of its turn, skipping any earlier ones."
+            ],
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "transactionAccount",
+      "docs": [
+        "A proposed transaction stored on-chain, in preparation to be executed"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "cryptidAccount",
+            "docs": [
+              "The cryptid account for the transaction"
+            ],
+            "type": "publicKey"
+          },
+          {
+            "name": "did",
+            "docs": [
+              "The owner of the cryptid account (Typically a DID account)"
+            ],
+            "type": "publicKey"
+          },
+          {
+            "name": "accounts",
+            "docs": [
+              "The accounts `instructions` references (excluding the cryptid account)"
+            ],
+            "type": {
+              "vec": "publicKey"
+            }
+          },
+          {
+            "name": "instructions",
+            "docs": [
+              "The instructions that will be executed"
+            ],
+            "type": {
+              "vec": {
+                "defined": "AbbreviatedInstructionData"
+              }
+            }
+          },
+          {
+            "name": "approvedMiddlewares",
+            "docs": [
+              "The middleware PDAs that have approved the transaction.",
+              "Space is reserved for these when the transaction is proposed, see [`crate::state::cryptid_account::CryptidAccount::max_middleware_approvals`].",
+              "Approvals beyond the reserved space are rejected"
+            ],
+            "type": {
+              "vec": "publicKey"
+            }
+          },
+          {
+            "name": "slot",
+            "docs": [
+              "The slot in which the transaction was proposed"
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "expiry",
+            "docs": [
+              "The point after which the transaction can no longer be approved or executed, if any"
+            ],
+            "type": {
+              "option": {
+                "defined": "TransactionExpiry"
+              }
+            }
+          },
+          {
+            "name": "state",
+            "docs": [
+              "The transaction state, to prevent replay attacks",
+              "in case an executed transaction account is not immediately",
+              "garbage-collected by the runtime"
+            ],
+            "type": {
+              "defined": "TransactionState"
+            }
+          },
+          {
+            "name": "unauthorizedSigner",
+            "docs": [
+              "If the transaction account is proposed by an authority on the DID, (the standard case)",
+              "then this is set to None.",
+              "If the transaction account is proposed by an unauthorized cryptid client, then",
+              "it is set to to that signer, and only a `superUser` middleware can approve it."
+            ],
+            "type": {
+              "option": "publicKey"
+            }
+          },
+          {
+            "name": "authorized",
+            "type": "bool"
+          },
+          {
+            "name": "approvers",
+            "docs": [
+              "The DID authorities that have approved the transaction, counting towards the cryptid account threshold",
+              "Cleared if the transaction is extended"
+            ],
+            "type": {
+              "vec": "publicKey"
+            }
+          },
+          {
+            "name": "payer",
+            "docs": [
+              "The account that paid the rent for the transaction account when it was proposed"
+            ],
+            "type": "publicKey"
+          },
+          {
+            "name": "flags",
+            "docs": [
+              "The [`TransactionFlags`] the transaction was proposed with"
+            ],
+            "type": "u8"
+          },
+          {
+            "name": "sequence",
+            "docs": [
+              "The position of the transaction in the cryptid account's sequence,",
+              "if it was proposed with `propose_sequenced_transaction`"
+            ],
+            "type": {
+              "option": "u64"
+            }
+          }
+        ]
+      }
+    }
+  ],
+  "types": [
+    {
+      "name": "AbbreviatedAccountMeta",
+      "docs": [
+        "An account for an instruction, similar to Solana's [`AccountMeta`](AccountMeta)"
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "key",
+            "docs": [
+              "The key of the account"
+            ],
+            "type": "u8"
           },
           {
-            "name": "superuserMiddleware",
+            "name": "meta",
             "docs": [
-              "Middlewares that have \"Superuser\" status on the cryptid account"
+              "Information about the account"
             ],
-            "type": {
-              "vec": "publicKey"
-            }
+            "type": "u8"
           }
         ]
       }
     },
     {
-      "name": "transactionAccount",
+      "name": "AbbreviatedInstructionData",
       "docs": [
-        "A proposed transaction stored on-chain, in preparation to be executed"
+        "The data about an instruction to be executed. Similar to Solana's [`Instruction`](SolanaInstruction).",
+        "Accounts are stored as indices in AbbreviatedAccountMeta to save space"
       ],
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "cryptidAccount",
-            "docs": [
-              "The cryptid account for the transaction"
-            ],
-            "type": "publicKey"
-          },
-          {
-            "name": "did",
+            "name": "programId",
             "docs": [
-              "The owner of the cryptid account (Typically a DID account)"
+              "The program to execute"
             ],
-            "type": "publicKey"
+            "type": "u8"
           },
           {
             "name": "accounts",
             "docs": [
-              "The accounts `instructions` references (excluding the cryptid account)"
+              "The accounts to send to the program"
             ],
             "type": {
-              "vec": "publicKey"
+              "vec": {
+                "defined": "AbbreviatedAccountMeta"
+              }
             }
           },
           {
-            "name": "instructions",
+            "name": "data",
             "docs": [
-              "The instructions that will be executed"
+              "The data for the instruction"
             ],
-            "type": {
-              "vec": {
-                "defined": "AbbreviatedInstructionData"
-              }
-            }
-          },
+            "type": "bytes"
+          }
+        ]
+      }
+    },
+    {
+      "name": "LegacyCryptidAccount",
+      "docs": [
+        "The layout of cryptid accounts created before middleware pipelines, thresholds and transaction tracking",
+        "were added to [`CryptidAccount`]. These can no longer be deserialized as a [`CryptidAccount`],",
+        "and are upgraded with `migrate_cryptid_account`."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
           {
-            "name": "approvedMiddleware",
+            "name": "middleware",
             "docs": [
-              "The most recent middleware PDA that approved the transaction"
+              "The middleware, if any, used by this cryptid account"
             ],
             "type": {
               "option": "publicKey"
             }
           },
           {
-            "name": "slot",
-            "docs": [
-              "The slot in which the transaction was proposed",
-              "This is used to prevent replay attacks"
-            ],
-            "type": "u8"
-          },
-          {
-            "name": "state",
+            "name": "index",
             "docs": [
-              "The transaction state, to prevent replay attacks",
-              "in case an executed transaction account is not immediately",
-              "garbage-collected by the runtime"
+              "The index of this cryptid account"
             ],
-            "type": {
-              "defined": "TransactionState"
-            }
+            "type": "u32"
           },
           {
-            "name": "unauthorizedSigner",
+            "name": "superuserMiddleware",
             "docs": [
-              "If the transaction account is proposed by an authority on the DID, (the standard case)",
-              "then this is set to None.",
-              "If the transaction account is proposed by an unauthorized cryptid client, then",
-              "it is set to to that signer, and only a `superUser` middleware can approve it."
+              "Middlewares that have \"Superuser\" status on the cryptid account"
             ],
             "type": {
-              "option": "publicKey"
+              "vec": "publicKey"
             }
-          },
-          {
-            "name": "authorized",
-            "type": "bool"
           }
         ]
       }
-    }
-  ],
-  "types": [
+    },
     {
-      "name": "AbbreviatedAccountMeta",
+      "name": "DIDReference",
       "docs": [
-        "An account for an instruction, similar to Solana's [`AccountMeta`](AccountMeta)"
+        "A struct that represents a DID, eg for use in a controller chain in a cryptid instruction",
+        "The account index is the index of the did account in the all_accounts array.",
+        "The DID may be generative or non-generative.",
+        "In the generative case, the did_account provides no information, as it is empty and owned",
+        "by the system program.",
+        "Therefore, the authority key is also needed in order to derive the did account with the correct identifier etc."
       ],
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "key",
+            "name": "accountIndex",
             "docs": [
-              "The key of the account"
+              "The index in the all_accounts array for the DID Account"
             ],
             "type": "u8"
           },
           {
-            "name": "meta",
+            "name": "authorityKey",
             "docs": [
-              "Information about the account"
+              "The did authority key (did:sol:<authority_key>)."
             ],
-            "type": "u8"
+            "type": "publicKey"
           }
         ]
       }
     },
     {
-      "name": "AbbreviatedInstructionData",
+      "name": "InstructionSize",
       "docs": [
-        "The data about an instruction to be executed. Similar to Solana's [`Instruction`](SolanaInstruction).",
-        "Accounts are stored as indices in AbbreviatedAccountMeta to save space"
+        "A helper struct for calculating [`InstructionData`] size"
       ],
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "programId",
+            "name": "accounts",
             "docs": [
-              "The program to execute"
+              "The number of accounts in the instruction"
             ],
             "type": "u8"
           },
-          {
-            "name": "accounts",
-            "docs": [
-              "The accounts to send to the program"
-            ],
-            "type": {
-              "vec": {
-                "defined": "AbbreviatedAccountMeta"
-              }
-            }
+          {
+            "name": "dataLen",
+            "docs": [
+              "The size of the instruction data"
+            ],
+            "type": "u16"
+          }
+        ]
+      }
+    },
+    {
+      "name": "TransactionExpiry",
+      "docs": [
+        "The point after which a proposed [`TransactionAccount`] can no longer be approved or executed"
+      ],
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "Slot",
+            "fields": [
+              "u64"
+            ]
+          },
+          {
+            "name": "UnixTimestamp",
+            "fields": [
+              "i64"
+            ]
+          }
+        ]
+      }
+    },
+    {
+      "name": "TransactionState",
+      "docs": [
+        "A [`TransactionAccount`]'s state"
+      ],
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "NotReady"
+          },
+          {
+            "name": "Ready"
+          },
+          {
+            "name": "Executed"
+          },
+          {
+            "name": "Executing",
+            "fields": [
+              {
+                "name": "next_index",
+                "docs": [
+                  "The index of the next instruction to execute"
+                ],
+                "type": "u16"
+              }
+            ]
+          }
+        ]
+      }
+    }
+  ],
+  "events": [
+    {
+      "name": "CryptidAccountCreated",
+      "fields": [
+        {
+          "name": "cryptidAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "did",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "authority",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "index",
+          "type": "u32",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "CryptidAccountUpdated",
+      "fields": [
+        {
+          "name": "cryptidAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "did",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "authority",
+          "type": "publicKey",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "CryptidAccountClosed",
+      "fields": [
+        {
+          "name": "cryptidAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "did",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "authority",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "destination",
+          "type": "publicKey",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "TransactionProposed",
+      "fields": [
+        {
+          "name": "cryptidAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "did",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transactionAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "authority",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "state",
+          "type": {
+            "defined": "TransactionState"
+          },
+          "index": false
+        },
+        {
+          "name": "sequence",
+          "type": {
+            "option": "u64"
+          },
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "TransactionExtended",
+      "fields": [
+        {
+          "name": "cryptidAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "did",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transactionAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "authority",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "instructionCount",
+          "type": "u16",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "TransactionStateChanged",
+      "fields": [
+        {
+          "name": "cryptidAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "did",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transactionAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "authority",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "previousState",
+          "type": {
+            "defined": "TransactionState"
+          },
+          "index": false
+        },
+        {
+          "name": "state",
+          "type": {
+            "defined": "TransactionState"
           },
-          {
-            "name": "data",
-            "docs": [
-              "The data for the instruction"
-            ],
-            "type": "bytes"
-          }
-        ]
-      }
+          "index": false
+        }
+      ]
     },
     {
-      "name": "DIDReference",
-      "docs": [
-        "A struct that represents a DID, eg for use in a controller chain in a cryptid instruction",
-        "The account index is the index of the did account in the all_accounts array.",
-        "The DID may be generative or non-generative.",
-        "In the generative case, the did_account provides no information, as it is empty and owned",
-        "by the system program.",
-        "Therefore, the authority key is also needed in order to derive the did account with the correct identifier etc."
-      ],
-      "type": {
-        "kind": "struct",
-        "fields": [
-          {
-            "name": "accountIndex",
-            "docs": [
-              "The index in the all_accounts array for the DID Account"
-            ],
-            "type": "u8"
-          },
-          {
-            "name": "authorityKey",
-            "docs": [
-              "The did authority key (did:sol:<authority_key>)."
-            ],
-            "type": "publicKey"
-          }
-        ]
-      }
+      "name": "TransactionApproved",
+      "fields": [
+        {
+          "name": "cryptidAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "did",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transactionAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "authority",
+          "type": "publicKey",
+          "index": false
+        }
+      ]
     },
     {
-      "name": "InstructionSize",
-      "docs": [
-        "A helper struct for calculating [`InstructionData`] size"
-      ],
-      "type": {
-        "kind": "struct",
-        "fields": [
-          {
-            "name": "accounts",
-            "docs": [
-              "The number of accounts in the instruction"
-            ],
-            "type": "u8"
-          },
-          {
-            "name": "dataLen",
-            "docs": [
-              "The size of the instruction data"
-            ],
-            "type": "u16"
-          }
-        ]
-      }
+      "name": "MiddlewareApproved",
+      "fields": [
+        {
+          "name": "cryptidAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "did",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transactionAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "middlewareAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "middlewareProgram",
+          "type": "publicKey",
+          "index": false
+        }
+      ]
     },
     {
-      "name": "TransactionState",
-      "docs": [
-        "A [`TransactionAccount`]'s state"
-      ],
-      "type": {
-        "kind": "enum",
-        "variants": [
-          {
-            "name": "NotReady"
+      "name": "SuperuserApproved",
+      "fields": [
+        {
+          "name": "cryptidAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "did",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transactionAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "middlewareAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "middlewareProgram",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "authorized",
+          "type": "bool",
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "TransactionExecuted",
+      "fields": [
+        {
+          "name": "cryptidAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "did",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transactionAccount",
+          "type": {
+            "option": "publicKey"
           },
-          {
-            "name": "Ready"
+          "index": false
+        },
+        {
+          "name": "authority",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "programIds",
+          "type": {
+            "vec": "publicKey"
           },
-          {
-            "name": "Executed"
-          }
-        ]
-      }
+          "index": false
+        }
+      ]
+    },
+    {
+      "name": "TransactionClosed",
+      "fields": [
+        {
+          "name": "cryptidAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "did",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "transactionAccount",
+          "type": "publicKey",
+          "index": false
+        },
+        {
+          "name": "authority",
+          "type": {
+            "option": "publicKey"
+          },
+          "index": false
+        },
+        {
+          "name": "destination",
+          "type": "publicKey",
+          "index": false
+        }
+      ]
     }
   ],
   "errors": [
@@ -1713,7 +4100,172 @@ export const IDL: Cryptid = {
     {
       "code": 6017,
       "name": "AlreadyAuthorizedTransactionAccount",
-      "msg": "Already authorized Transaction Account."
+      "msg": "Transaction Account is already authorized and cannot be authorized again."
+    },
+    {
+      "code": 6018,
+      "name": "OpenTransactionAccounts",
+      "msg": "The cryptid account cannot be closed while it has open transaction accounts."
+    },
+    {
+      "code": 6019,
+      "name": "DirectExecuteWithMiddleware",
+      "msg": "Cryptid accounts with middleware cannot use direct execution. Propose the transaction instead."
+    },
+    {
+      "code": 6020,
+      "name": "InvalidThreshold",
+      "msg": "The threshold of a cryptid account must be at least one."
+    },
+    {
+      "code": 6021,
+      "name": "AlreadyApproved",
+      "msg": "The authority has already approved the transaction."
+    },
+    {
+      "code": 6022,
+      "name": "TransactionExpired",
+      "msg": "The transaction has expired."
+    },
+    {
+      "code": 6023,
+      "name": "TransactionNotReclaimable",
+      "msg": "The transaction account has not expired or been open long enough to be reclaimed."
+    },
+    {
+      "code": 6024,
+      "name": "InvalidRentDestination",
+      "msg": "The rent for the transaction account can only be refunded to its payer."
+    },
+    {
+      "code": 6025,
+      "name": "SequenceOnGenerativeAccount",
+      "msg": "Sequenced transactions can only be proposed for non-generative cryptid accounts."
+    },
+    {
+      "code": 6026,
+      "name": "InvalidTransactionSequence",
+      "msg": "The transaction sequence number does not match the cryptid account."
+    },
+    {
+      "code": 6027,
+      "name": "InvalidAccountMeta",
+      "msg": "The account meta contains unknown flags."
+    },
+    {
+      "code": 6028,
+      "name": "MissingInstructionAccount",
+      "msg": "An instruction references an account that was not provided."
+    },
+    {
+      "code": 6029,
+      "name": "InvalidAccountCount",
+      "msg": "The number of accounts passed to execute does not match those in the transaction account."
+    },
+    {
+      "code": 6030,
+      "name": "AtomicTransaction",
+      "msg": "Only transactions proposed as non-atomic can be executed in chunks."
+    },
+    {
+      "code": 6031,
+      "name": "InvalidChunkSize",
+      "msg": "A chunk must execute at least one instruction."
+    },
+    {
+      "code": 6032,
+      "name": "AccountAlreadyInUse",
+      "msg": "The account to be created is already in use."
+    },
+    {
+      "code": 6033,
+      "name": "CryptidAccountNotRentExempt",
+      "msg": "The cryptid account must remain rent-exempt."
+    },
+    {
+      "code": 6034,
+      "name": "CryptidAccountModified",
+      "msg": "The cryptid account was modified by a sub-instruction."
+    },
+    {
+      "code": 6035,
+      "name": "ReentrantInstruction",
+      "msg": "Instructions executed by cryptid cannot invoke the cryptid program."
+    },
+    {
+      "code": 6036,
+      "name": "UnsatisfiableSigner",
+      "msg": "An instruction requires a signature from an account that cannot sign."
+    },
+    {
+      "code": 6037,
+      "name": "UnsatisfiableWritable",
+      "msg": "An instruction requires an account that cannot be written to be writable."
+    },
+    {
+      "code": 6038,
+      "name": "NotUpgradeAuthority",
+      "msg": "The signer is not the upgrade authority of the cryptid program."
+    },
+    {
+      "code": 6039,
+      "name": "NotRegistryAuthority",
+      "msg": "The signer is not the authority of the approval registry."
+    },
+    {
+      "code": 6040,
+      "name": "UnregisteredMiddleware",
+      "msg": "The middleware account is not owned by a registered middleware program."
+    },
+    {
+      "code": 6041,
+      "name": "DuplicateMiddleware",
+      "msg": "A middleware cannot appear more than once in the middleware pipeline."
+    },
+    {
+      "code": 6042,
+      "name": "MiddlewareNotInPipeline",
+      "msg": "The middleware is not in the middleware pipeline of the cryptid account."
+    },
+    {
+      "code": 6043,
+      "name": "TooManyInstructions",
+      "msg": "A non-atomic transaction cannot hold this many instructions."
+    },
+    {
+      "code": 6044,
+      "name": "InvalidExecuteFlags",
+      "msg": "Unknown execute flags."
+    },
+    {
+      "code": 6045,
+      "name": "OutOfOrderTransaction",
+      "msg": "Sequenced transactions must be executed or closed in the order in which they were proposed."
+    },
+    {
+      "code": 6046,
+      "name": "NoSpaceForMiddlewareApproval",
+      "msg": "The transaction account has no space for another middleware approval. Propose the transaction again."
+    },
+    {
+      "code": 6047,
+      "name": "NotLegacyCryptidAccount",
+      "msg": "The cryptid account is not in the legacy layout."
+    },
+    {
+      "code": 6048,
+      "name": "ImmutableCryptidProgram",
+      "msg": "The cryptid program is immutable, so the approval registry can no longer be initialized."
+    },
+    {
+      "code": 6049,
+      "name": "MiddlewareApprovalRequired",
+      "msg": "The middleware of the cryptid account must approve changes to it."
+    },
+    {
+      "code": 6050,
+      "name": "UnauthorizedSequencedTransaction",
+      "msg": "Sequenced transactions can only be proposed by an authority on the DID."
     }
   ]
 };
@@ -70,6 +70,14 @@ export type SuperuserCheckSigner = {
           "name": "cryptidProgram",
           "isMut": false,
           "isSigner": false
+        },
+        {
+          "name": "approvalRegistry",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The registry of middleware programs that can approve cryptid transactions"
+          ]
         }
       ],
       "args": []
@@ -187,6 +195,14 @@ export const IDL: SuperuserCheckSigner = {
           "name": "cryptidProgram",
           "isMut": false,
           "isSigner": false
+        },
+        {
+          "name": "approvalRegistry",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The registry of middleware programs that can approve cryptid transactions"
+          ]
         }
       ],
       "args": []
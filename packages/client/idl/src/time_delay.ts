@@ -100,6 +100,22 @@ export type TimeDelay = {
           "name": "cryptidProgram",
           "isMut": false,
           "isSigner": false
+        },
+        {
+          "name": "approvalRegistry",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The registry of middleware programs that can approve cryptid transactions"
+          ]
+        },
+        {
+          "name": "cryptidAccount",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The cryptid account the transaction was proposed for"
+          ]
         }
       ],
       "args": [
@@ -264,6 +280,22 @@ export const IDL: TimeDelay = {
           "name": "cryptidProgram",
           "isMut": false,
           "isSigner": false
+        },
+        {
+          "name": "approvalRegistry",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The registry of middleware programs that can approve cryptid transactions"
+          ]
+        },
+        {
+          "name": "cryptidAccount",
+          "isMut": false,
+          "isSigner": false,
+          "docs": [
+            "The cryptid account the transaction was proposed for"
+          ]
         }
       ],
       "args": [
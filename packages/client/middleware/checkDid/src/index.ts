@@ -1,4 +1,5 @@
 import {
+  APPROVAL_REGISTRY,
   CRYPTID_PROGRAM,
   ExecuteMiddlewareParams,
   GenericMiddlewareParams,
@@ -110,6 +111,8 @@ export class CheckDidMiddleware
         transactionAccount: params.transactionAccount,
         did: params.cryptidAccountDetails.didAccount,
         authority: params.authority.publicKey,
+        approvalRegistry: APPROVAL_REGISTRY,
+        cryptidAccount: params.cryptidAccountDetails.address,
         cryptidProgram: CRYPTID_PROGRAM,
       })
       .instruction()
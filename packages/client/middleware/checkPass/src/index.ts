@@ -1,4 +1,5 @@
 import {
+  APPROVAL_REGISTRY,
   CRYPTID_PROGRAM,
   ExecuteMiddlewareParams,
   GenericMiddlewareParams,
@@ -138,6 +139,8 @@ export class CheckPassMiddleware
         authority: params.authority.publicKey,
         expireFeatureAccount,
         gatewayToken,
+        approvalRegistry: APPROVAL_REGISTRY,
+        cryptidAccount: params.cryptidAccountDetails.address,
         cryptidProgram: CRYPTID_PROGRAM,
         gatewayProgram: GATEWAY_PROGRAM,
       })
@@ -1,4 +1,5 @@
 import {
+  APPROVAL_REGISTRY,
   CRYPTID_PROGRAM,
   ExecuteMiddlewareParams,
   GenericMiddlewareParams,
@@ -89,6 +90,8 @@ export class CheckRecipientMiddleware
       .accounts({
         middlewareAccount: params.middlewareAccount,
         transactionAccount: params.transactionAccount,
+        approvalRegistry: APPROVAL_REGISTRY,
+        cryptidAccount: params.cryptidAccountDetails.address,
         cryptidProgram: CRYPTID_PROGRAM,
       })
       .instruction();
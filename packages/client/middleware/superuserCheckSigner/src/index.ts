@@ -1,4 +1,5 @@
 import {
+  APPROVAL_REGISTRY,
   CRYPTID_PROGRAM,
   ExecuteMiddlewareParams,
   GenericMiddlewareParams,
@@ -89,6 +90,7 @@ export class SuperuserCheckSignerMiddleware
         middlewareAccount: params.middlewareAccount,
         transactionAccount: params.transactionAccount,
         cryptidAccount: params.cryptidAccountDetails.address,
+        approvalRegistry: APPROVAL_REGISTRY,
         cryptidProgram: CRYPTID_PROGRAM,
       })
       .instruction();
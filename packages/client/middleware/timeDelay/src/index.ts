@@ -1,4 +1,5 @@
 import {
+  APPROVAL_REGISTRY,
   CRYPTID_PROGRAM,
   ExecuteMiddlewareParams,
   GenericMiddlewareParams,
@@ -124,6 +125,8 @@ export class TimeDelayMiddleware
         transactionAccount: params.transactionAccount,
         transactionCreateTime: transactionStateAddress,
         destination: params.authority.publicKey,
+        approvalRegistry: APPROVAL_REGISTRY,
+        cryptidAccount: params.cryptidAccountDetails.address,
         cryptidProgram: CRYPTID_PROGRAM,
       })
       .instruction()
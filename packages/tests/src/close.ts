@@ -1,11 +1,16 @@
-import { Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
 import chai from "chai";
 import chaiAsPromised from "chai-as-promised";
 import { cryptidTestCases, makeTransfer } from "./util/cryptid";
 import { didTestCases } from "./util/did";
-import { createTestContext, fund } from "./util/anchorUtils";
-import { DID_SOL_PREFIX } from "@identity.com/sol-did-client";
-import { CryptidClient, TransactionState } from "@identity.com/cryptid";
+import {
+  balanceOf,
+  createTestContext,
+  CryptidTestContext,
+  fund,
+} from "./util/anchorUtils";
+import { DID_SOL_PREFIX, DID_SOL_PROGRAM } from "@identity.com/sol-did-client";
+import { Cryptid, CryptidClient, TransactionState } from "@identity.com/cryptid";
 
 chai.use(chaiAsPromised);
 const { expect } = chai;
@@ -77,4 +82,86 @@ didTestCases.forEach(({ didType, getDidAccount }) => {
       });
     });
   });
+
+  describe(`closeCryptidAccount (${didType} DID)`, () => {
+    const context = createTestContext();
+    const { provider, authority } = context;
+    const did = DID_SOL_PREFIX + ":" + authority.publicKey;
+
+    const recipient = Keypair.generate();
+    const destination = Keypair.generate();
+
+    let didAccount: PublicKey;
+    let cryptid: CryptidClient;
+
+    const closeCryptidAccount = ({
+      program,
+      authority,
+    }: CryptidTestContext = context) =>
+      program.methods
+        .closeCryptidAccount(
+          [], // no controller chain
+          cryptid.details.bump,
+          cryptid.details.index,
+          cryptid.details.didAccountBump
+        )
+        .accounts({
+          cryptidAccount: cryptid.address(),
+          did: didAccount,
+          didProgram: DID_SOL_PROGRAM,
+          authority: authority.publicKey,
+          destination: destination.publicKey,
+        })
+        .rpc();
+
+    before(`Set up ${didType} DID account`, async () => {
+      await fund(authority.publicKey, 10 * LAMPORTS_PER_SOL);
+      [didAccount] = await getDidAccount(authority);
+    });
+
+    before("Set up an initialized Cryptid Account", async () => {
+      cryptid = await Cryptid.createFromDID(did, authority, [], {
+        connection: provider.connection,
+      });
+
+      await fund(cryptid.address(), 5 * LAMPORTS_PER_SOL);
+    });
+
+    it("cannot close a cryptid account as a non-authority", async () => {
+      const nonAuthority = createTestContext();
+      await fund(nonAuthority.authority.publicKey);
+
+      return expect(closeCryptidAccount(nonAuthority)).to.be.rejectedWith(
+        "Error Code: KeyMustBeSigner"
+      );
+    });
+
+    it("cannot close a cryptid account with open transactions", async () => {
+      const { proposeTransaction, transactionAccount, proposeSigners } =
+        await cryptid.propose(
+          makeTransfer(cryptid.address(), recipient.publicKey)
+        );
+      await cryptid.send(proposeTransaction, proposeSigners);
+
+      await expect(closeCryptidAccount()).to.be.rejectedWith(
+        "Error Code: OpenTransactionAccounts"
+      );
+
+      // the cryptid account can be closed once its transactions are
+      const { transactions, signers } = await cryptid.close(
+        transactionAccount
+      );
+      await cryptid.send(transactions[0], signers);
+    });
+
+    it("can close a cryptid account, sending its lamports to the destination", async () => {
+      const cryptidBalance = await balanceOf(cryptid.address());
+
+      await closeCryptidAccount();
+
+      expect(await balanceOf(destination.publicKey)).to.equal(cryptidBalance);
+      expect(await provider.connection.getAccountInfo(cryptid.address())).to.be
+        .null;
+    });
+  });
 });
@@ -5,8 +5,14 @@ import {
   PublicKey,
   SystemProgram,
 } from "@solana/web3.js";
+import { SimulateError } from "@project-serum/anchor";
 import chai from "chai";
 import chaiAsPromised from "chai-as-promised";
+import {
+  createAmountToUiAmountInstruction,
+  createMint,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
 import {
   cryptidTransferInstruction,
   makeTransfer,
@@ -26,7 +32,7 @@ const { expect } = chai;
 
 didTestCases.forEach(({ didType, getDidAccount }) => {
   describe(`directExecute (${didType} DID)`, () => {
-    const { program, authority, provider } = createTestContext();
+    const { program, authority, provider, keypair } = createTestContext();
 
     let didAccount: PublicKey;
     let cryptid: CryptidClient;
@@ -188,6 +194,104 @@ didTestCases.forEach(({ didType, getDidAccount }) => {
       expect(previousBalance).to.equal(2 * LAMPORTS_PER_SOL);
     });
 
+    it("forwards the return data of the last instruction only", async () => {
+      const recipient = Keypair.generate();
+      const mint = await createMint(
+        provider.connection,
+        keypair,
+        keypair.publicKey,
+        null,
+        2
+      );
+
+      // the token program returns the amount as a UI string ("0.01")
+      const amountToUiAmountInstructionData: InstructionData = {
+        programId: 7, // The token program - index 3 in remainingAccounts
+        accounts: [{ key: 6, meta: 0 }], // the mint
+        data: createAmountToUiAmountInstruction(mint, 1).data,
+      };
+      const simulate = (instructions: InstructionData[]) =>
+        program.methods
+          .directExecute(
+            [], // no controller chain
+            instructions,
+            cryptid.details.bump,
+            cryptid.details.index,
+            cryptid.details.didAccountBump,
+            0 // flags
+          )
+          .accounts({
+            cryptidAccount: cryptid.address(),
+            didProgram: DID_SOL_PROGRAM,
+            did: didAccount,
+            authority: authority.publicKey,
+          })
+          .remainingAccounts([
+            toAccountMeta(recipient.publicKey, true, false),
+            toAccountMeta(SystemProgram.programId),
+            toAccountMeta(mint),
+            toAccountMeta(TOKEN_PROGRAM_ID),
+          ])
+          .simulate();
+      const cryptidReturnLog = `Program return: ${program.programId} `;
+
+      const { raw: returningLogs } = await simulate([
+        transferInstructionData,
+        amountToUiAmountInstructionData,
+      ]);
+      expect(returningLogs).to.include(
+        cryptidReturnLog + Buffer.from("0.01").toString("base64")
+      );
+
+      // the transfer returns nothing, so the earlier return data is not forwarded
+      const { raw: nonReturningLogs } = await simulate([
+        amountToUiAmountInstructionData,
+        transferInstructionData,
+      ]);
+      expect(
+        nonReturningLogs.some((log) => log.startsWith(cryptidReturnLog))
+      ).to.equal(false);
+    });
+
+    it("logs the index of the failing sub-instruction", async () => {
+      const recipient = Keypair.generate();
+
+      // the second transfer exceeds the balance of the cryptid account
+      const error: SimulateError = await program.methods
+        .directExecute(
+          [], // no controller chain
+          [
+            transferInstructionData,
+            cryptidTransferInstruction(1_000 * LAMPORTS_PER_SOL),
+          ],
+          cryptid.details.bump,
+          cryptid.details.index,
+          cryptid.details.didAccountBump,
+          0 // flags
+        )
+        .accounts({
+          cryptidAccount: cryptid.address(),
+          didProgram: DID_SOL_PROGRAM,
+          did: didAccount,
+          authority: authority.publicKey,
+        })
+        .remainingAccounts([
+          toAccountMeta(recipient.publicKey, true, false),
+          toAccountMeta(SystemProgram.programId),
+        ])
+        .simulate()
+        .catch((error) => error);
+
+      // a failed sub-instruction aborts the transaction, so it is the last one logged
+      const executionLogs = (error.simulationResponse.logs ?? []).filter(
+        (log) => log.startsWith("Program log: Executing sub-instruction")
+      );
+      expect(executionLogs).to.deep.equal([
+        `Program log: Executing sub-instruction 0 (program ${SystemProgram.programId})`,
+        `Program log: Executing sub-instruction 1 (program ${SystemProgram.programId})`,
+      ]);
+    });
+
     // Test-case for the initialized DID case only.
     if (didType === TestType.Initialized) {
       it("can transfer through Cryptid with a second key on the DID", async () => {
@@ -1,11 +1,19 @@
 import { DID_SOL_PREFIX } from "@identity.com/sol-did-client";
-import { LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
 import chai from "chai";
 import chaiAsPromised from "chai-as-promised";
 import { makeTransfer } from "./util/cryptid";
 import { initializeDIDAccount } from "./util/did";
-import { createTestContext, fund } from "./util/anchorUtils";
-import { Cryptid, CryptidClient } from "@identity.com/cryptid";
+import {
+  createTestContext,
+  fund,
+  registryAuthorityProgram,
+} from "./util/anchorUtils";
+import {
+  APPROVAL_REGISTRY,
+  Cryptid,
+  CryptidClient,
+} from "@identity.com/cryptid";
 
 chai.use(chaiAsPromised);
 const { expect } = chai;
@@ -50,6 +58,34 @@ describe(`Middleware`, () => {
       .accounts({
         transactionAccount,
         middlewareAccount: authority.publicKey, // not a correct middleware account
+        approvalRegistry: APPROVAL_REGISTRY,
+        cryptidAccount: cryptid.address(),
+      })
+      .rpc();
+    return expect(shouldFail).to.be.rejectedWith(
+      "Error Code: UnregisteredMiddleware."
+    );
+  });
+
+  it("cannot update the approval registry without its authority", async () => {
+    const shouldFail = program.methods
+      .updateApprovalRegistry([authority.publicKey], [])
+      .accounts({
+        approvalRegistry: APPROVAL_REGISTRY,
+        authority: authority.publicKey,
+      })
+      .rpc();
+    return expect(shouldFail).to.be.rejectedWith(
+      "Error Code: NotRegistryAuthority."
+    );
+  });
+
+  it("cannot register the system program as a middleware program", async () => {
+    const shouldFail = registryAuthorityProgram.methods
+      .updateApprovalRegistry([SystemProgram.programId], [])
+      .accounts({
+        approvalRegistry: APPROVAL_REGISTRY,
+        authority: registryAuthorityProgram.provider.publicKey,
       })
       .rpc();
     return expect(shouldFail).to.be.rejectedWith(
@@ -28,6 +28,7 @@ const { expect } = chai;
 
 describe("Middleware chaining", () => {
   const {
+    program,
     provider,
     authority,
     keypair,
@@ -146,6 +147,18 @@ describe("Middleware chaining", () => {
 
   beforeEach("Set up Cryptid Account with middleware", setUpCryptidClient);
 
+  it("stores the middleware pipeline on the cryptid account", async () => {
+    const cryptidAccount = await program.account.cryptidAccount.fetch(
+      cryptid.address()
+    );
+
+    const middlewares = cryptidAccount.middlewares.map((m) => m.toBase58());
+    expect(middlewares).to.deep.equal([
+      checkPassMiddlewareAccount.toBase58(),
+      timeDelayMiddlewareAccount.toBase58(),
+    ]);
+  });
+
   it("blocks a transfer with no gateway token", async () => {
     // no gateway token exists for the authority
 
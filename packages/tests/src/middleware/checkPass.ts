@@ -30,6 +30,7 @@ import { GatekeeperService } from "@identity.com/solana-gatekeeper-lib";
 import { getGatewayTokenAddressForOwnerAndGatekeeperNetwork } from "@identity.com/solana-gateway-ts";
 import { beforeEach } from "mocha";
 import {
+  APPROVAL_REGISTRY,
   CheckPassMiddleware,
   Cryptid,
   CRYPTID_PROGRAM,
@@ -153,6 +154,8 @@ describe("Middleware: checkPass", () => {
         cryptid.details.didAccountBump,
         TransactionState.toBorsh(TransactionState.Ready),
         false,
+        null, // no expiry
+        0, // no flags
         [instruction],
         2
       )
@@ -205,6 +208,8 @@ describe("Middleware: checkPass", () => {
         authority: authority.publicKey,
         expireFeatureAccount,
         gatewayToken,
+        approvalRegistry: APPROVAL_REGISTRY,
+        cryptidAccount: cryptid.address(),
         cryptidProgram: CRYPTID_PROGRAM,
         gatewayProgram: GATEWAY_PROGRAM,
       })
@@ -9,7 +9,7 @@ import chaiAsPromised from "chai-as-promised";
 import { makeTransfer } from "../util/cryptid";
 import { initializeDIDAccount } from "../util/did";
 import { balanceOf, createTestContext, fund } from "../util/anchorUtils";
-import { DID_SOL_PREFIX } from "@identity.com/sol-did-client";
+import { DID_SOL_PREFIX, DID_SOL_PROGRAM } from "@identity.com/sol-did-client";
 import { Cryptid, TransactionState } from "@identity.com/cryptid";
 import {
   CheckRecipientMiddleware,
@@ -22,6 +22,7 @@ const { expect } = chai;
 
 describe("Middleware: checkRecipient", () => {
   const {
+    program,
     keypair,
     provider,
     authority,
@@ -118,6 +119,67 @@ describe("Middleware: checkRecipient", () => {
     );
   });
 
+  it("blocks a direct execution that bypasses the middleware", async () => {
+    const signedTransaction = await cryptid.directExecute(makeTransaction());
+    const shouldFail = cryptid.send(signedTransaction);
+
+    return expect(shouldFail).to.be.rejectedWith(
+      "Error Code: DirectExecuteWithMiddleware"
+    );
+  });
+
+  it("blocks removing the middleware to directly execute in the same transaction", async () => {
+    const removeMiddleware = await program.methods
+      .updateCryptidAccount(
+        [], // no middleware
+        [], // no superuser middleware to add
+        [], // no superuser middleware to remove
+        1, // threshold
+        null, // no reclaim age
+        [], // no controller chain
+        cryptid.details.index,
+        cryptid.details.didAccountBump
+      )
+      .accounts({
+        cryptidAccount: cryptid.address(),
+        didProgram: DID_SOL_PROGRAM,
+        did: cryptid.details.didAccount,
+        authority: authority.publicKey,
+      })
+      .instruction();
+    const directExecute = await cryptid.directExecute(makeTransaction());
+
+    const shouldFail = cryptid.send(
+      new Transaction().add(removeMiddleware, ...directExecute.instructions)
+    );
+
+    return expect(shouldFail).to.be.rejectedWith(
+      "Error Code: MiddlewareApprovalRequired"
+    );
+  });
+
+  it("blocks closing the cryptid account without the middleware", async () => {
+    const shouldFail = program.methods
+      .closeCryptidAccount(
+        [], // no controller chain
+        cryptid.details.bump,
+        cryptid.details.index,
+        cryptid.details.didAccountBump
+      )
+      .accounts({
+        cryptidAccount: cryptid.address(),
+        did: cryptid.details.didAccount,
+        didProgram: DID_SOL_PROGRAM,
+        authority: authority.publicKey,
+        destination: authority.publicKey,
+      })
+      .rpc();
+
+    return expect(shouldFail).to.be.rejectedWith(
+      "Error Code: MiddlewareApprovalRequired"
+    );
+  });
+
   it("blocks a transfer to a different recipient", async () => {
     // change the recipient
     recipient = Keypair.generate();
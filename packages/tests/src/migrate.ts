@@ -0,0 +1,55 @@
+import { LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import chai from "chai";
+import chaiAsPromised from "chai-as-promised";
+import { fund, createTestContext } from "./util/anchorUtils";
+
+chai.use(chaiAsPromised);
+const { expect } = chai;
+
+// A cryptid account with index 1 and no middleware, in the legacy layout (see Anchor.toml)
+const LEGACY_CRYPTID_ACCOUNT = new PublicKey(
+  "8Vy8pP8jZZbmdobpCBpUi8xF1szhRoKTQzX9xysF46Vu"
+);
+
+describe("migrate", () => {
+  const { program, provider, authority } = createTestContext();
+
+  const migrate = () =>
+    program.methods
+      .migrateCryptidAccount()
+      .accounts({
+        cryptidAccount: LEGACY_CRYPTID_ACCOUNT,
+        payer: authority.publicKey,
+      })
+      .rpc();
+
+  before("Fund the payer", () => fund(authority.publicKey, LAMPORTS_PER_SOL));
+
+  it("can migrate a cryptid account in the legacy layout", async () => {
+    await migrate();
+
+    const cryptidAccount = await program.account.cryptidAccount.fetch(
+      LEGACY_CRYPTID_ACCOUNT
+    );
+    expect(cryptidAccount.index).to.equal(1);
+    expect(cryptidAccount.middlewares).to.be.empty;
+    expect(cryptidAccount.superuserMiddleware).to.be.empty;
+    expect(cryptidAccount.threshold).to.equal(1);
+    expect(cryptidAccount.openTransactions).to.equal(0);
+
+    // the migrated account is resized, and topped up by the payer to remain rent-exempt
+    const accountInfo = await provider.connection.getAccountInfo(
+      LEGACY_CRYPTID_ACCOUNT
+    );
+    const minimumBalance =
+      await provider.connection.getMinimumBalanceForRentExemption(
+        accountInfo?.data.length ?? 0
+      );
+    expect(accountInfo?.lamports).to.be.at.least(minimumBalance);
+  });
+
+  it("cannot migrate a cryptid account twice", () =>
+    expect(migrate()).to.be.rejectedWith(
+      "Error Code: NotLegacyCryptidAccount"
+    ));
+});
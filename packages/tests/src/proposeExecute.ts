@@ -12,10 +12,15 @@ import {
   makeTransfer,
   toAccountMeta,
 } from "./util/cryptid";
-import { didTestCases } from "./util/did";
-import { fund, createTestContext, balanceOf } from "./util/anchorUtils";
+import { didTestCases, TestType } from "./util/did";
+import {
+  fund,
+  createTestContext,
+  balanceOf,
+  waitForSlot,
+} from "./util/anchorUtils";
 import { DID_SOL_PREFIX, DID_SOL_PROGRAM } from "@identity.com/sol-did-client";
-import { web3 } from "@project-serum/anchor";
+import { BN, web3 } from "@project-serum/anchor";
 import {
   CryptidClient,
   InstructionData,
@@ -46,7 +51,8 @@ didTestCases.forEach(({ didType, getDidAccount }) => {
 
       const propose = async (
         transactionAccount: Keypair,
-        instruction: InstructionData = transferInstructionData
+        instruction: InstructionData = transferInstructionData,
+        expirySlot: number | null = null
       ) =>
         program.methods
           .proposeTransaction(
@@ -56,6 +62,8 @@ didTestCases.forEach(({ didType, getDidAccount }) => {
             cryptid.details.didAccountBump,
             TransactionState.toBorsh(TransactionState.Ready),
             false,
+            expirySlot === null ? null : { slot: [new BN(expirySlot)] },
+            0, // no flags
             [instruction],
             2
           )
@@ -73,7 +81,7 @@ didTestCases.forEach(({ didType, getDidAccount }) => {
           .signers([transactionAccount])
           .rpc();
 
-      const execute = (transactionAccount: Keypair) =>
+      const execute = (transactionAccount: { publicKey: PublicKey }) =>
         // execute the Cryptid transaction
         program.methods
           .executeTransaction(
@@ -97,6 +105,58 @@ didTestCases.forEach(({ didType, getDidAccount }) => {
           ])
           .rpc();
 
+      const proposeNonAtomic = (transactionAccount: Keypair) =>
+        program.methods
+          .proposeTransaction(
+            [], // no controller chain,
+            cryptid.details.bump,
+            cryptid.details.index,
+            cryptid.details.didAccountBump,
+            TransactionState.toBorsh(TransactionState.Ready),
+            false,
+            null, // no expiry
+            2, // non-atomic
+            [transferInstructionData, transferInstructionData],
+            2
+          )
+          .accounts({
+            cryptidAccount: cryptid.address(),
+            didProgram: DID_SOL_PROGRAM,
+            did: didAccount,
+            authority: authority.publicKey,
+            transactionAccount: transactionAccount.publicKey,
+          })
+          .remainingAccounts([
+            toAccountMeta(recipient.publicKey, true, false),
+            toAccountMeta(SystemProgram.programId),
+          ])
+          .signers([transactionAccount])
+          .rpc();
+
+      const executeChunk = (transactionAccount: Keypair) =>
+        program.methods
+          .executeTransactionChunk(
+            [], // no controller chain,
+            cryptid.details.bump,
+            cryptid.details.index,
+            cryptid.details.didAccountBump,
+            0,
+            1 // one instruction per chunk
+          )
+          .accounts({
+            cryptidAccount: cryptid.address(),
+            didProgram: DID_SOL_PROGRAM,
+            did: didAccount,
+            authority: authority.publicKey,
+            destination: authority.publicKey,
+            transactionAccount: transactionAccount.publicKey,
+          })
+          .remainingAccounts([
+            toAccountMeta(recipient.publicKey, true, false),
+            toAccountMeta(SystemProgram.programId),
+          ])
+          .rpc();
+
       before(`Set up ${didType} DID account`, async () => {
         await fund(authority.publicKey, 10 * LAMPORTS_PER_SOL);
         [didAccount] = await getDidAccount(authority);
@@ -144,6 +204,40 @@ didTestCases.forEach(({ didType, getDidAccount }) => {
         return expect(shouldFail).to.be.rejected;
       });
 
+      it("cannot execute with more accounts than were proposed", async () => {
+        const transactionAccount = Keypair.generate();
+
+        await propose(transactionAccount);
+
+        // pad the proposed accounts with an additional account
+        const shouldFail = program.methods
+          .executeTransaction(
+            [], // no controller chain
+            cryptid.details.bump,
+            cryptid.details.index,
+            cryptid.details.didAccountBump,
+            0
+          )
+          .accounts({
+            cryptidAccount: cryptid.address(),
+            didProgram: DID_SOL_PROGRAM,
+            did: didAccount,
+            authority: authority.publicKey,
+            destination: authority.publicKey,
+            transactionAccount: transactionAccount.publicKey,
+          })
+          .remainingAccounts([
+            toAccountMeta(recipient.publicKey, true, false),
+            toAccountMeta(SystemProgram.programId),
+            toAccountMeta(Keypair.generate().publicKey),
+          ])
+          .rpc();
+
+        return expect(shouldFail).to.be.rejectedWith(
+          "Error Code: InvalidAccountCount"
+        );
+      });
+
       it("can propose and execute a transfer using the Cryptid client", async () => {
         const previousBalance = await balanceOf(cryptid.address());
 
@@ -178,6 +272,8 @@ didTestCases.forEach(({ didType, getDidAccount }) => {
             cryptid.details.didAccountBump,
             TransactionState.toBorsh(TransactionState.Ready),
             false,
+            null, // no expiry
+            0, // no flags
             [transferInstructionData],
             2
           )
@@ -322,6 +418,350 @@ didTestCases.forEach(({ didType, getDidAccount }) => {
         );
       });
 
+      it("rejects a proposal requiring a signature that can never be given", async () => {
+        const transactionAccount = Keypair.generate();
+
+        const instructionDataWithProgramSigner =
+          cryptidTransferInstruction(LAMPORTS_PER_SOL); // 1 SOL
+        // require the system program to sign the transfer
+        (
+          instructionDataWithProgramSigner.accounts as TransactionAccountMeta[]
+        )[1] = {
+          key: 5, // the system program
+          meta: 1, // signer
+        };
+
+        const shouldFail = propose(
+          transactionAccount,
+          instructionDataWithProgramSigner
+        );
+
+        return expect(shouldFail).to.be.rejectedWith(
+          "Error Code: UnsatisfiableSigner"
+        );
+      });
+
+      it("rejects a proposal that executes a nested cryptid transaction", async () => {
+        const nestedTransactionAccount = Keypair.generate();
+        await propose(nestedTransactionAccount);
+
+        // an instruction executing the other transaction through cryptid
+        const nestedExecuteInstructionData: InstructionData = {
+          programId: 4, // The cryptid program - index 0 in remainingAccounts
+          accounts: [],
+          data: program.coder.instruction.encode("executeTransaction", {
+            controllerChain: [],
+            cryptidAccountBump: cryptid.details.bump,
+            cryptidAccountIndex: cryptid.details.index,
+            didAccountBump: cryptid.details.didAccountBump,
+            flags: 0,
+          }),
+        };
+
+        const transactionAccount = Keypair.generate();
+        const shouldFail = program.methods
+          .proposeTransaction(
+            [], // no controller chain,
+            cryptid.details.bump,
+            cryptid.details.index,
+            cryptid.details.didAccountBump,
+            TransactionState.toBorsh(TransactionState.Ready),
+            false,
+            null, // no expiry
+            0, // no flags
+            [nestedExecuteInstructionData],
+            1
+          )
+          .accounts({
+            cryptidAccount: cryptid.address(),
+            didProgram: DID_SOL_PROGRAM,
+            did: didAccount,
+            authority: authority.publicKey,
+            transactionAccount: transactionAccount.publicKey,
+          })
+          .remainingAccounts([toAccountMeta(program.programId)])
+          .signers([transactionAccount])
+          .rpc();
+
+        return expect(shouldFail).to.be.rejectedWith("ReentrantInstruction");
+      });
+
+      if (cryptidType === TestType.Generative) {
+        it("rejects the execution if it reassigns the cryptid account", async () => {
+          const transactionAccount = Keypair.generate();
+
+          const assignInstructionData: InstructionData = {
+            programId: 5, // The System program
+            accounts: [
+              {
+                key: 0, // the cryptid account
+                meta: 3, // writable signer
+              },
+            ],
+            data: SystemProgram.assign({
+              accountPubkey: PublicKey.default,
+              programId: recipient.publicKey,
+            }).data,
+          };
+
+          await propose(transactionAccount, assignInstructionData);
+          const shouldFail = execute(transactionAccount);
+
+          return expect(shouldFail).to.be.rejectedWith(
+            "CryptidAccountModified"
+          );
+        });
+      }
+
+      if (cryptidType === TestType.Initialized) {
+        // propose a transfer at the next sequenced transaction account
+        const proposeSequenced = async ({
+          allowUnauthorized = false,
+          expirySlot = null as number | null,
+        } = {}): Promise<PublicKey> => {
+          const { nextTransactionSequence } =
+            await program.account.cryptidAccount.fetch(cryptid.address());
+          const [transactionAccount] = PublicKey.findProgramAddressSync(
+            [
+              Buffer.from("transaction_account"),
+              cryptid.address().toBuffer(),
+              nextTransactionSequence.toArrayLike(Buffer, "le", 8),
+            ],
+            program.programId
+          );
+
+          await program.methods
+            .proposeSequencedTransaction(
+              [], // no controller chain,
+              cryptid.details.bump,
+              cryptid.details.index,
+              cryptid.details.didAccountBump,
+              nextTransactionSequence,
+              TransactionState.toBorsh(TransactionState.Ready),
+              allowUnauthorized,
+              expirySlot === null ? null : { slot: [new BN(expirySlot)] },
+              0, // no flags
+              [transferInstructionData],
+              2
+            )
+            .accounts({
+              cryptidAccount: cryptid.address(),
+              didProgram: DID_SOL_PROGRAM,
+              did: didAccount,
+              authority: authority.publicKey,
+              transactionAccount,
+            })
+            .remainingAccounts([
+              toAccountMeta(recipient.publicKey, true, false),
+              toAccountMeta(SystemProgram.programId),
+            ])
+            .rpc();
+
+          return transactionAccount;
+        };
+
+        it("can propose and execute a transfer at a sequenced transaction account", async () => {
+          const previousBalance = await balanceOf(cryptid.address());
+
+          const { nextTransactionSequence } =
+            await program.account.cryptidAccount.fetch(cryptid.address());
+          const transactionAccount = await proposeSequenced();
+
+          const cryptidAccount = await program.account.cryptidAccount.fetch(
+            cryptid.address()
+          );
+          expect(cryptidAccount.nextTransactionSequence.toNumber()).to.equal(
+            nextTransactionSequence.toNumber() + 1
+          );
+
+          await execute({ publicKey: transactionAccount });
+
+          const currentBalance = await balanceOf(cryptid.address());
+          expect(previousBalance - currentBalance).to.equal(LAMPORTS_PER_SOL);
+        });
+
+        it("executes sequenced transactions in the order they were proposed", async () => {
+          const first = await proposeSequenced();
+          const second = await proposeSequenced();
+
+          const shouldFail = execute({ publicKey: second });
+          await expect(shouldFail).to.be.rejectedWith("OutOfOrderTransaction");
+
+          await execute({ publicKey: first });
+          await execute({ publicKey: second });
+
+          const { nextExecutableSequence, nextTransactionSequence } =
+            await program.account.cryptidAccount.fetch(cryptid.address());
+          expect(nextExecutableSequence.toNumber()).to.equal(
+            nextTransactionSequence.toNumber()
+          );
+        });
+
+        it("cannot propose a sequenced transaction as a non-authority", () =>
+          expect(
+            proposeSequenced({ allowUnauthorized: true })
+          ).to.be.rejectedWith("UnauthorizedSequencedTransaction"));
+
+        it("can reclaim an expired sequenced transaction ahead of its turn", async () => {
+          const first = await proposeSequenced();
+          const expirySlot = (await provider.connection.getSlot()) + 2;
+          const second = await proposeSequenced({ expirySlot });
+          await waitForSlot(expirySlot);
+
+          await program.methods
+            .reclaimTransaction()
+            .accounts({
+              cryptidAccount: cryptid.address(),
+              payer: authority.publicKey,
+              transactionAccount: second,
+            })
+            .rpc();
+
+          // the sequence has moved past the expired transaction, skipping the one before it
+          const { nextExecutableSequence, nextTransactionSequence } =
+            await program.account.cryptidAccount.fetch(cryptid.address());
+          expect(nextExecutableSequence.toNumber()).to.equal(
+            nextTransactionSequence.toNumber()
+          );
+          await expect(execute({ publicKey: first })).to.be.rejectedWith(
+            "OutOfOrderTransaction"
+          );
+
+          // the skipped transaction can still be closed
+          const { transactions, signers } = await cryptid.close(first);
+          await cryptid.send(transactions[0], signers);
+          expect(await provider.connection.getAccountInfo(first)).to.be.null;
+        });
+      }
+
+      it("can execute a non-atomic transaction in chunks", async () => {
+        const previousBalance = await balanceOf(cryptid.address());
+        const transactionAccount = Keypair.generate();
+
+        await proposeNonAtomic(transactionAccount);
+
+        await executeChunk(transactionAccount);
+
+        const { state } = await program.account.transactionAccount.fetch(
+          transactionAccount.publicKey
+        );
+        expect(state).to.deep.equal({ executing: { nextIndex: 1 } });
+        expect(previousBalance - (await balanceOf(cryptid.address()))).to.equal(
+          LAMPORTS_PER_SOL
+        );
+
+        await executeChunk(transactionAccount);
+
+        expect(previousBalance - (await balanceOf(cryptid.address()))).to.equal(
+          2 * LAMPORTS_PER_SOL
+        );
+        // the transaction account is closed once the last chunk is executed
+        expect(
+          await provider.connection.getAccountInfo(transactionAccount.publicKey)
+        ).to.be.null;
+      });
+
+      it("lets anyone reclaim an expired transaction, refunding the rent to its payer", async () => {
+        const transactionAccount = Keypair.generate();
+        const expirySlot = (await provider.connection.getSlot()) + 2;
+        await propose(transactionAccount, transferInstructionData, expirySlot);
+        await waitForSlot(expirySlot);
+
+        const { program: thirdPartyProgram, authority: thirdParty } =
+          createTestContext();
+        await fund(thirdParty.publicKey);
+        const rent = await balanceOf(transactionAccount.publicKey);
+        const previousBalance = await balanceOf(authority.publicKey);
+
+        // the third party pays the transaction fee, so the payer receives exactly the rent
+        await thirdPartyProgram.methods
+          .reclaimTransaction()
+          .accounts({
+            cryptidAccount: cryptid.address(),
+            payer: authority.publicKey,
+            transactionAccount: transactionAccount.publicKey,
+          })
+          .rpc();
+
+        const currentBalance = await balanceOf(authority.publicKey);
+        expect(currentBalance - previousBalance).to.equal(rent);
+        expect(
+          await provider.connection.getAccountInfo(transactionAccount.publicKey)
+        ).to.be.null;
+      });
+
+      it("can close, but not reclaim, a transaction part-way through chunked execution", async () => {
+        const previousBalance = await balanceOf(cryptid.address());
+        const transactionAccount = Keypair.generate();
+        await proposeNonAtomic(transactionAccount);
+        await executeChunk(transactionAccount);
+
+        const shouldFail = program.methods
+          .reclaimTransaction()
+          .accounts({
+            cryptidAccount: cryptid.address(),
+            payer: authority.publicKey,
+            transactionAccount: transactionAccount.publicKey,
+          })
+          .rpc();
+        await expect(shouldFail).to.be.rejectedWith("InvalidTransactionState");
+
+        // the DID authority can abandon the remaining instructions
+        await program.methods
+          .closeTransaction(
+            [], // no controller chain,
+            cryptid.details.bump,
+            cryptid.details.index,
+            cryptid.details.didAccountBump
+          )
+          .accounts({
+            cryptidAccount: cryptid.address(),
+            didProgram: DID_SOL_PROGRAM,
+            did: didAccount,
+            authority: authority.publicKey,
+            destination: authority.publicKey,
+            transactionAccount: transactionAccount.publicKey,
+          })
+          .rpc();
+
+        expect(previousBalance - (await balanceOf(cryptid.address()))).to.equal(
+          LAMPORTS_PER_SOL
+        );
+        expect(
+          await provider.connection.getAccountInfo(transactionAccount.publicKey)
+        ).to.be.null;
+      });
+
+      it("cannot execute an atomic transaction in chunks", async () => {
+        const transactionAccount = Keypair.generate();
+        await propose(transactionAccount);
+
+        const shouldFail = program.methods
+          .executeTransactionChunk(
+            [], // no controller chain,
+            cryptid.details.bump,
+            cryptid.details.index,
+            cryptid.details.didAccountBump,
+            0,
+            1
+          )
+          .accounts({
+            cryptidAccount: cryptid.address(),
+            didProgram: DID_SOL_PROGRAM,
+            did: didAccount,
+            authority: authority.publicKey,
+            destination: authority.publicKey,
+            transactionAccount: transactionAccount.publicKey,
+          })
+          .remainingAccounts([
+            toAccountMeta(recipient.publicKey, true, false),
+            toAccountMeta(SystemProgram.programId),
+          ])
+          .rpc();
+
+        return expect(shouldFail).to.be.rejectedWith("AtomicTransaction");
+      });
+
       it("rejects the propose if the signer is not a valid signer on the DID", async () => {
         const transactionAccount = Keypair.generate();
 
@@ -337,6 +777,8 @@ didTestCases.forEach(({ didType, getDidAccount }) => {
             cryptid.details.didAccountBump,
             TransactionState.toBorsh(TransactionState.Ready),
             false,
+            null, // no expiry
+            0, // no flags
             [transferInstructionData],
             2
           )
@@ -0,0 +1,230 @@
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import chai from "chai";
+import chaiAsPromised from "chai-as-promised";
+import { makeTransfer, toAccountMeta } from "./util/cryptid";
+import {
+  addKeyToDID,
+  initializeDIDAccount,
+  removeKeyFromDID,
+} from "./util/did";
+import { balanceOf, createTestContext, fund } from "./util/anchorUtils";
+import { DID_SOL_PREFIX, DID_SOL_PROGRAM } from "@identity.com/sol-did-client";
+import { Cryptid, CryptidClient } from "@identity.com/cryptid";
+
+chai.use(chaiAsPromised);
+const { expect } = chai;
+
+describe("threshold", () => {
+  const { program, provider, authority } = createTestContext();
+  const did = DID_SOL_PREFIX + ":" + authority.publicKey;
+
+  const approver = Keypair.generate();
+  const recipient = Keypair.generate();
+
+  let didAccount: PublicKey;
+  let cryptid: CryptidClient;
+  let approverFragment: string;
+  // closed before closing the cryptid account, if not executed
+  const proposed: PublicKey[] = [];
+
+  const makeTransaction = () =>
+    makeTransfer(cryptid.address(), recipient.publicKey);
+
+  const propose = async (): Promise<PublicKey> => {
+    const { proposeTransaction, transactionAccount, proposeSigners } =
+      await cryptid.propose(makeTransaction());
+    await cryptid.send(proposeTransaction, proposeSigners);
+    proposed.push(transactionAccount);
+    return transactionAccount;
+  };
+
+  const approve = (transactionAccount: PublicKey, signer: Keypair) =>
+    program.methods
+      .approveTransaction(
+        [], // no controller chain
+        cryptid.details.bump,
+        cryptid.details.index,
+        cryptid.details.didAccountBump
+      )
+      .accounts({
+        cryptidAccount: cryptid.address(),
+        did: didAccount,
+        didProgram: DID_SOL_PROGRAM,
+        authority: signer.publicKey,
+        transactionAccount,
+      })
+      .signers([signer])
+      .rpc();
+
+  // additional authorities sign as remaining accounts, after the (empty) controller chain
+  const update = (threshold: number, coSigners: Keypair[] = []) =>
+    program.methods
+      .updateCryptidAccount(
+        [], // no middleware
+        [], // no superuser middleware to add
+        [], // no superuser middleware to remove
+        threshold,
+        null, // no reclaim age
+        [], // no controller chain
+        cryptid.details.index,
+        cryptid.details.didAccountBump
+      )
+      .accounts({
+        cryptidAccount: cryptid.address(),
+        didProgram: DID_SOL_PROGRAM,
+        did: didAccount,
+        authority: authority.publicKey,
+      })
+      .remainingAccounts(
+        coSigners.map((coSigner) =>
+          toAccountMeta(coSigner.publicKey, false, true)
+        )
+      )
+      .signers(coSigners)
+      .rpc();
+
+  const close = (coSigners: Keypair[] = []) =>
+    program.methods
+      .closeCryptidAccount(
+        [], // no controller chain
+        cryptid.details.bump,
+        cryptid.details.index,
+        cryptid.details.didAccountBump
+      )
+      .accounts({
+        cryptidAccount: cryptid.address(),
+        did: didAccount,
+        didProgram: DID_SOL_PROGRAM,
+        authority: authority.publicKey,
+        destination: recipient.publicKey,
+      })
+      .remainingAccounts(
+        coSigners.map((coSigner) =>
+          toAccountMeta(coSigner.publicKey, false, true)
+        )
+      )
+      .signers(coSigners)
+      .rpc();
+
+  const execute = async (transactionAccount: PublicKey) => {
+    const { transactions, signers } = await cryptid.execute(
+      transactionAccount
+    );
+    return cryptid.send(transactions[0], signers);
+  };
+
+  before("Set up a DID with two authorities", async () => {
+    await fund(authority.publicKey, 10 * LAMPORTS_PER_SOL);
+    await fund(approver.publicKey);
+    [didAccount] = await initializeDIDAccount(authority);
+  });
+
+  before("Set up a Cryptid Account requiring two authorities", async () => {
+    cryptid = await Cryptid.createFromDID(did, authority, [], {
+      connection: provider.connection,
+      accountIndex: 1,
+    });
+    await fund(cryptid.address(), 20 * LAMPORTS_PER_SOL);
+
+    // the threshold is initially 1, so the authority can raise it alone
+    await update(2);
+  });
+
+  beforeEach("Add the approver to the DID", async () => {
+    approverFragment = await addKeyToDID(authority, approver.publicKey);
+  });
+
+  afterEach("Remove the approver from the DID", async () => {
+    if (approverFragment) {
+      await removeKeyFromDID(authority, approverFragment);
+    }
+  });
+
+  it("cannot execute without enough authorities", async () => {
+    const transactionAccount = await propose();
+
+    return expect(execute(transactionAccount)).to.be.rejectedWith(
+      "NotEnoughSigners"
+    );
+  });
+
+  it("can execute once another authority has approved", async () => {
+    const previousBalance = await balanceOf(cryptid.address());
+    const transactionAccount = await propose();
+    await approve(transactionAccount, approver);
+
+    await execute(transactionAccount);
+
+    const currentBalance = await balanceOf(cryptid.address());
+    expect(previousBalance - currentBalance).to.equal(LAMPORTS_PER_SOL);
+  });
+
+  it("does not count an approver that has since been removed from the DID", async () => {
+    const transactionAccount = await propose();
+    await approve(transactionAccount, approver);
+
+    await removeKeyFromDID(authority, approverFragment);
+    approverFragment = "";
+
+    return expect(execute(transactionAccount)).to.be.rejectedWith(
+      "NotEnoughSigners"
+    );
+  });
+
+  it("can execute with another authority co-signing after the proposed accounts", async () => {
+    const previousBalance = await balanceOf(cryptid.address());
+    const transactionAccount = await propose();
+
+    const { transactions, signers } = await cryptid.execute(
+      transactionAccount
+    );
+    const executeInstruction = transactions[0].instructions.find(
+      (instruction) => instruction.programId.equals(program.programId)
+    );
+    executeInstruction?.keys.push(
+      toAccountMeta(approver.publicKey, false, true)
+    );
+    await cryptid.send(transactions[0], [...signers, approver]);
+
+    const currentBalance = await balanceOf(cryptid.address());
+    expect(previousBalance - currentBalance).to.equal(LAMPORTS_PER_SOL);
+  });
+
+  it("cannot lower the threshold without enough authorities", () =>
+    expect(update(1)).to.be.rejectedWith("NotEnoughSigners"));
+
+  it("can update the cryptid account with another authority co-signing", async () => {
+    await update(2, [approver]);
+
+    const { threshold } = await program.account.cryptidAccount.fetch(
+      cryptid.address()
+    );
+    expect(threshold).to.equal(2);
+  });
+
+  it("cannot close the cryptid account without enough authorities", () =>
+    expect(close()).to.be.rejectedWith("NotEnoughSigners"));
+
+  it("can close the cryptid account with another authority co-signing", async () => {
+    // a cryptid account cannot be closed while it has open transactions
+    for (const transactionAccount of proposed) {
+      if (await provider.connection.getAccountInfo(transactionAccount)) {
+        const { transactions, signers } = await cryptid.close(
+          transactionAccount
+        );
+        await cryptid.send(transactions[0], signers);
+      }
+    }
+
+    const cryptidBalance = await balanceOf(cryptid.address());
+    const previousBalance = await balanceOf(recipient.publicKey);
+
+    await close([approver]);
+
+    expect(await balanceOf(recipient.publicKey)).to.equal(
+      previousBalance + cryptidBalance
+    );
+    expect(await provider.connection.getAccountInfo(cryptid.address())).to.be
+      .null;
+  });
+});
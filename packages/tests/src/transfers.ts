@@ -4,6 +4,7 @@ import {
   Keypair,
   LAMPORTS_PER_SOL,
   PublicKey,
+  SystemProgram,
   Transaction,
 } from "@solana/web3.js";
 import { DID_SOL_PREFIX } from "@identity.com/sol-did-client";
@@ -226,4 +227,108 @@ describe(`Native & SPL Transfer tests`, () => {
       BigInt(10 ** SPL_DECIMALS)
     );
   });
+
+  it("CryptidAddress can fund and create a new account", async () => {
+    const newAccount = Keypair.generate();
+    const space = 100;
+    const lamports =
+      await provider.connection.getMinimumBalanceForRentExemption(space);
+    const previousBalance = await balanceOf(cryptid.address());
+
+    const transaction = new Transaction().add(
+      SystemProgram.createAccount({
+        fromPubkey: cryptid.address(),
+        newAccountPubkey: newAccount.publicKey,
+        lamports,
+        space,
+        programId: thridParty.publicKey,
+      })
+    );
+
+    const { proposeTransaction, proposeSigners, transactionAccount } =
+      await cryptid.propose(transaction);
+    await cryptid.send(proposeTransaction, [...proposeSigners]);
+
+    const { transactions, signers } = await cryptid.execute(transactionAccount);
+
+    await cryptid.send(transactions[0], [...signers, newAccount]);
+
+    const currentBalance = await balanceOf(cryptid.address());
+    expect(previousBalance - currentBalance).to.equal(lamports);
+
+    const accountInfo = await provider.connection.getAccountInfo(
+      newAccount.publicKey
+    );
+    expect(accountInfo?.lamports).to.equal(lamports);
+    expect(accountInfo?.data.length).to.equal(space);
+    expect(accountInfo?.owner.toBase58()).to.equal(
+      thridParty.publicKey.toBase58()
+    );
+  });
+
+  it("CryptidAddress can fund and create a new account with a seed", async () => {
+    const seed = "cryptid-test";
+    const space = 100;
+    const newAccount = await PublicKey.createWithSeed(
+      cryptid.address(),
+      seed,
+      thridParty.publicKey
+    );
+    const lamports =
+      await provider.connection.getMinimumBalanceForRentExemption(space);
+    const previousBalance = await balanceOf(cryptid.address());
+
+    const transaction = new Transaction().add(
+      SystemProgram.createAccountWithSeed({
+        fromPubkey: cryptid.address(),
+        newAccountPubkey: newAccount,
+        basePubkey: cryptid.address(),
+        seed,
+        lamports,
+        space,
+        programId: thridParty.publicKey,
+      })
+    );
+
+    const { proposeTransaction, proposeSigners, transactionAccount } =
+      await cryptid.propose(transaction);
+    await cryptid.send(proposeTransaction, [...proposeSigners]);
+
+    const { transactions, signers } = await cryptid.execute(transactionAccount);
+
+    await cryptid.send(transactions[0], [...signers]);
+
+    const currentBalance = await balanceOf(cryptid.address());
+    expect(previousBalance - currentBalance).to.equal(lamports);
+
+    const accountInfo = await provider.connection.getAccountInfo(newAccount);
+    expect(accountInfo?.data.length).to.equal(space);
+    expect(accountInfo?.owner.toBase58()).to.equal(
+      thridParty.publicKey.toBase58()
+    );
+  });
+
+  it("CryptidAddress cannot send SOL below its rent-exempt minimum", async () => {
+    const balance = await balanceOf(cryptid.address());
+
+    const transaction = new Transaction().add(
+      SystemProgram.transfer({
+        fromPubkey: cryptid.address(),
+        toPubkey: authority.publicKey,
+        lamports: balance,
+      })
+    );
+
+    const { proposeTransaction, proposeSigners, transactionAccount } =
+      await cryptid.propose(transaction);
+    await cryptid.send(proposeTransaction, [...proposeSigners]);
+
+    const { transactions, signers } = await cryptid.execute(transactionAccount);
+
+    const shouldFail = cryptid.send(transactions[0], [...signers]);
+
+    return expect(shouldFail).to.be.rejectedWith(
+      "CryptidAccountNotRentExempt"
+    );
+  });
 });
@@ -9,6 +9,11 @@ import {
   TimeDelay,
   SuperuserCheckSigner,
 } from "@identity.com/cryptid-idl";
+import { APPROVAL_REGISTRY } from "@identity.com/cryptid";
+
+const BPF_LOADER_UPGRADEABLE_PROGRAM_ID = new PublicKey(
+  "BPFLoaderUpgradeab1e11111111111111111111111"
+);
 
 const envProvider = anchor.AnchorProvider.env();
 const envProgram = anchor.workspace.Cryptid as Program<Cryptid>;
@@ -33,6 +38,49 @@ if (!process.env.QUIET) {
     envProvider.connection.removeOnLogsListener(logListener);
   });
 }
+// The program deployer is the upgrade authority of the cryptid program, and so the approval registry authority
+export const registryAuthorityProgram = envProgram;
+
+before("Register middleware programs", async () => {
+  const middlewarePrograms = [
+    envCheckRecipientMiddlewareProgram.programId,
+    envCheckPassMiddlewareProgram.programId,
+    envCheckDidMiddlewareProgram.programId,
+    envTimeDelayMiddlewareProgram.programId,
+    envSuperuserCheckSignerMiddlewareProgram.programId,
+  ];
+  const approvalRegistry =
+    await envProgram.account.approvalRegistry.fetchNullable(APPROVAL_REGISTRY);
+
+  if (approvalRegistry) {
+    await envProgram.methods
+      .updateApprovalRegistry(middlewarePrograms, [])
+      .accounts({
+        approvalRegistry: APPROVAL_REGISTRY,
+        authority: envProvider.wallet.publicKey,
+      })
+      .rpc();
+    return;
+  }
+
+  const [programData] = PublicKey.findProgramAddressSync(
+    [envProgram.programId.toBuffer()],
+    BPF_LOADER_UPGRADEABLE_PROGRAM_ID
+  );
+  await envProgram.methods
+    .initializeApprovalRegistry(
+      middlewarePrograms,
+      envProvider.wallet.publicKey
+    )
+    .accounts({
+      approvalRegistry: APPROVAL_REGISTRY,
+      authority: envProvider.wallet.publicKey,
+      cryptidProgram: envProgram.programId,
+      programData,
+    })
+    .rpc();
+});
+
 // The exported Anchor wallet type is messed up at the moment, so we define it indirectly here
 export type Wallet = AnchorProvider["wallet"];
 
@@ -133,3 +181,10 @@ export const createTestContext = (): CryptidTestContext => {
 
 export const sleep = (ms: number): Promise<void> =>
   new Promise((resolve) => setTimeout(resolve, ms));
+
+// wait until the cluster has passed the given slot, e.g. for a transaction to expire
+export const waitForSlot = async (slot: number): Promise<void> => {
+  while ((await envProvider.connection.getSlot()) <= slot) {
+    await sleep(400);
+  }
+};
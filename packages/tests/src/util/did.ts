@@ -10,19 +10,34 @@ import { CLUSTER } from "./constants";
 import { Wallet } from "./anchorUtils";
 import { utils, Wallet as EthWallet } from "ethers";
 
-export const addKeyToDID = async (authority: Wallet, key: PublicKey) => {
+// Returns the fragment of the new verification method
+export const addKeyToDID = async (
+  authority: Wallet,
+  key: PublicKey
+): Promise<string> => {
   const did = DidSolIdentifier.create(authority.publicKey, CLUSTER);
   const didSolService = DidSolService.build(did, {
     wallet: authority,
   });
+  const fragment = `key${Date.now()}`; // randomise fragment name, so that we can add multiple keys in multiple tests.
   const newKeyVerificationMethod = {
     flags: [BitwiseVerificationMethodFlag.CapabilityInvocation],
-    fragment: `key${Date.now()}`, // randomise fragment name, so that we can add multiple keys in multiple tests.
+    fragment,
     keyData: key.toBuffer(),
     methodType: VerificationMethodType.Ed25519VerificationKey2018,
   };
 
   await didSolService.addVerificationMethod(newKeyVerificationMethod).rpc(); //{ skipPreflight: true, commitment: 'finalized' });
+  return fragment;
+};
+
+export const removeKeyFromDID = async (authority: Wallet, fragment: string) => {
+  const did = DidSolIdentifier.create(authority.publicKey, CLUSTER);
+  const didSolService = DidSolService.build(did, {
+    wallet: authority,
+  });
+
+  await didSolService.removeVerificationMethod(fragment).rpc();
 };
 
 export const setControllersOnDid = async (
@@ -0,0 +1,225 @@
+//! Splits a large transaction into a `propose_transaction` instruction followed by as many
+//! `extend_transaction` instructions as needed, each fitting into a single Solana transaction.
+
+use crate::client::{extend_compiled_transaction, propose_compiled_transaction};
+use crate::client::{ClientError, CompiledInstructions, CryptidAddress};
+use crate::state::transaction_expiry::TransactionExpiry;
+use crate::state::transaction_state::TransactionState;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+
+/// The maximum size of a serialized Solana transaction
+/// (the IPv6 minimum MTU, less the IPv6 and fragment headers)
+pub const PACKET_DATA_SIZE: usize = 1280 - 40 - 8;
+
+/// The size of a transaction signature
+const SIGNATURE_SIZE: usize = 64;
+/// The size of the message header (required signatures, readonly signed and readonly unsigned accounts)
+const MESSAGE_HEADER_SIZE: usize = 3;
+
+/// The size of the compact-u16 encoding of `len`
+fn compact_len_size(len: usize) -> usize {
+    match len {
+        0..=0x7f => 1,
+        0x80..=0x3fff => 2,
+        _ => 3,
+    }
+}
+
+/// Estimate the size of a serialized transaction containing only `instruction`, signed by `signers` signers.
+/// The fee payer is assumed to be one of the accounts of the instruction.
+pub fn estimate_transaction_size(instruction: &Instruction, signers: usize) -> usize {
+    let mut keys: Vec<Pubkey> = vec![instruction.program_id];
+    for meta in instruction.accounts.iter() {
+        if !keys.contains(&meta.pubkey) {
+            keys.push(meta.pubkey);
+        }
+    }
+
+    let instruction_size = 1 // program id index
+        + compact_len_size(instruction.accounts.len()) + instruction.accounts.len()
+        + compact_len_size(instruction.data.len()) + instruction.data.len();
+
+    compact_len_size(signers) + SIGNATURE_SIZE * signers
+        + MESSAGE_HEADER_SIZE
+        + compact_len_size(keys.len()) + 32 * keys.len()
+        + 32 // recent blockhash
+        + compact_len_size(1) + instruction_size
+}
+
+/// Compile `instructions` into an ordered plan of a `propose_transaction` instruction,
+/// followed by `extend_transaction` instructions, each fitting under [`PACKET_DATA_SIZE`].
+/// The transaction is left in the Ready state by the last instruction of the plan.
+///
+/// `transaction_account` is created by the propose instruction, so must also sign it.
+pub fn compile_transaction(
+    address: &CryptidAddress,
+    authority: &Pubkey,
+    transaction_account: &Pubkey,
+    instructions: Vec<Instruction>,
+    allow_unauthorized: bool,
+    expiry: Option<TransactionExpiry>,
+    flags: u8,
+) -> std::result::Result<Vec<Instruction>, ClientError> {
+    let build = |chunk: &[Instruction], is_first: bool, state: TransactionState| {
+        let compiled = CompiledInstructions::compile(address, authority, chunk.to_vec())?;
+        if is_first {
+            propose_compiled_transaction(
+                address,
+                authority,
+                transaction_account,
+                compiled,
+                state,
+                allow_unauthorized,
+                expiry,
+                flags,
+            )
+        } else {
+            extend_compiled_transaction(address, authority, transaction_account, compiled, state)
+        }
+    };
+    // the propose instruction is also signed by the new transaction account
+    let fits = |chunk: &[Instruction], is_first: bool| -> Result<bool> {
+        let signers = if is_first { 2 } else { 1 };
+        let instruction = build(chunk, is_first, TransactionState::NotReady)?;
+        Ok(estimate_transaction_size(&instruction, signers) <= PACKET_DATA_SIZE)
+    };
+
+    let mut chunks: Vec<Vec<Instruction>> = vec![];
+    let mut current: Vec<Instruction> = vec![];
+    for instruction in instructions {
+        current.push(instruction);
+        if fits(&current, chunks.is_empty())? {
+            continue;
+        }
+
+        // start a new chunk with the instruction that did not fit
+        let instruction = current.pop().unwrap();
+        if current.is_empty() {
+            return Err(ClientError::INSTRUCTION_TOO_LARGE);
+        }
+        chunks.push(current);
+        current = vec![instruction];
+        if !fits(&current, false)? {
+            return Err(ClientError::INSTRUCTION_TOO_LARGE);
+        }
+    }
+    if !current.is_empty() || chunks.is_empty() {
+        chunks.push(current);
+    }
+
+    let last_index = chunks.len() - 1;
+    chunks
+        .iter()
+        .enumerate()
+        .map(|(index, chunk)| {
+            let state = if index == last_index {
+                TransactionState::Ready
+            } else {
+                TransactionState::NotReady
+            };
+            Ok(build(chunk, index == 0, state)?)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::instruction;
+    use anchor_lang::solana_program::system_instruction;
+    use anchor_lang::AnchorDeserialize;
+
+    fn transfers(address: &CryptidAddress, count: usize) -> Vec<Instruction> {
+        (0..count)
+            .map(|_| {
+                system_instruction::transfer(&address.cryptid_account, &Pubkey::new_unique(), 1)
+            })
+            .collect()
+    }
+
+    fn state_of(instruction: &Instruction, is_first: bool) -> TransactionState {
+        let mut data = &instruction.data[8..];
+        if is_first {
+            instruction::ProposeTransaction::deserialize(&mut data)
+                .unwrap()
+                .state
+        } else {
+            instruction::ExtendTransaction::deserialize(&mut data)
+                .unwrap()
+                .state
+        }
+    }
+
+    #[test]
+    fn small_transaction_is_a_single_propose() {
+        let authority = Pubkey::new_unique();
+        let address = CryptidAddress::new(&authority, 0);
+
+        let plan = compile_transaction(
+            &address,
+            &authority,
+            &Pubkey::new_unique(),
+            transfers(&address, 2),
+            false,
+            None,
+            0,
+        )
+        .unwrap();
+
+        assert_eq!(plan.len(), 1);
+        assert_eq!(state_of(&plan[0], true), TransactionState::Ready);
+    }
+
+    #[test]
+    fn large_transaction_is_split_into_extends() {
+        let authority = Pubkey::new_unique();
+        let address = CryptidAddress::new(&authority, 0);
+
+        // each transfer adds a new recipient, so only a limited number fit in one transaction
+        let plan = compile_transaction(
+            &address,
+            &authority,
+            &Pubkey::new_unique(),
+            transfers(&address, 60),
+            false,
+            None,
+            0,
+        )
+        .unwrap();
+
+        assert!(plan.len() > 1);
+        assert!(estimate_transaction_size(&plan[0], 2) <= PACKET_DATA_SIZE);
+        assert_eq!(state_of(&plan[0], true), TransactionState::NotReady);
+        for (index, instruction) in plan.iter().enumerate().skip(1) {
+            assert!(estimate_transaction_size(instruction, 1) <= PACKET_DATA_SIZE);
+            let expected_state = if index == plan.len() - 1 {
+                TransactionState::Ready
+            } else {
+                TransactionState::NotReady
+            };
+            assert_eq!(state_of(instruction, false), expected_state);
+        }
+    }
+
+    #[test]
+    fn oversized_instruction_is_rejected() {
+        let authority = Pubkey::new_unique();
+        let address = CryptidAddress::new(&authority, 0);
+        let oversized =
+            Instruction::new_with_bytes(Pubkey::new_unique(), &[0; PACKET_DATA_SIZE], vec![]);
+
+        assert!(matches!(
+            compile_transaction(
+                &address,
+                &authority,
+                &Pubkey::new_unique(),
+                vec![oversized],
+                false,
+                None,
+                0,
+            ),
+            Err(error) if error.is_instruction_too_large()
+        ));
+    }
+}
@@ -0,0 +1,646 @@
+//! Off-chain builders for cryptid instructions, enabled with the `client` feature.
+//!
+//! The builders take plain [`Instruction`]s, derive the PDAs and bumps for the cryptid and DID accounts,
+//! and order the accounts as expected by [`AllAccounts`](crate::instructions::util::AllAccounts):
+//! cryptid account, did, did program, authority, followed by the accounts of the instructions.
+//!
+//! Only authorities directly on the DID are supported - the builders do not construct controller chains.
+
+pub mod compiler;
+
+use crate::error::CryptidError;
+use crate::id;
+use crate::instructions::util::AUTHORITY_ACCOUNT_INDEX;
+use crate::state::abbreviated_instruction_data::AbbreviatedInstructionData;
+use crate::state::account_meta_props::AccountMetaProps;
+use crate::state::cryptid_account::CryptidAccount;
+use crate::state::transaction_account::TransactionAccount;
+use crate::state::transaction_expiry::TransactionExpiry;
+use crate::state::transaction_state::TransactionState;
+use crate::util::SolDID;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{system_program, InstructionData};
+use std::collections::HashMap;
+use std::fmt;
+
+/// The number of named accounts (cryptid account, did, did program, authority) before the remaining accounts
+pub const NAMED_ACCOUNTS_LEN: usize = AUTHORITY_ACCOUNT_INDEX + 1;
+
+/// An error raised while building cryptid instructions off-chain.
+/// This is a struct rather than a public enum, which the IDL would pick up as an on-chain type.
+#[derive(Debug)]
+pub struct ClientError(ClientErrorKind);
+
+#[derive(Debug)]
+enum ClientErrorKind {
+    /// An instruction is too large to be proposed or extended in a single Solana transaction.
+    InstructionTooLarge,
+    /// An instruction could not be built
+    Anchor(Error),
+}
+impl ClientError {
+    pub(crate) const INSTRUCTION_TOO_LARGE: Self =
+        ClientError(ClientErrorKind::InstructionTooLarge);
+
+    /// True if an instruction is too large to be proposed or extended in a single Solana transaction
+    pub fn is_instruction_too_large(&self) -> bool {
+        matches!(self.0, ClientErrorKind::InstructionTooLarge)
+    }
+
+    /// The error returned by the instruction builders, if an instruction could not be built
+    pub fn anchor_error(&self) -> Option<&Error> {
+        match &self.0 {
+            ClientErrorKind::Anchor(error) => Some(error),
+            ClientErrorKind::InstructionTooLarge => None,
+        }
+    }
+}
+impl From<Error> for ClientError {
+    fn from(error: Error) -> Self {
+        ClientError(ClientErrorKind::Anchor(error))
+    }
+}
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.0 {
+            ClientErrorKind::InstructionTooLarge => {
+                write!(
+                    f,
+                    "An instruction is too large to fit into a single transaction."
+                )
+            }
+            ClientErrorKind::Anchor(error) => fmt::Display::fmt(error, f),
+        }
+    }
+}
+impl std::error::Error for ClientError {}
+
+/// The seed prefix of sol_did accounts
+pub const DID_ACCOUNT_SEED: &[u8] = b"did-account";
+
+/// Derive the (generative or non-generative) DID account of `did:sol:<authority>`
+pub fn derive_did_account(authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[DID_ACCOUNT_SEED, authority.as_ref()], &SolDID::id())
+}
+
+/// Derive the cryptid account with index `index` on the DID account `did`
+pub fn derive_cryptid_account(did: &Pubkey, index: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            CryptidAccount::SEED_PREFIX,
+            SolDID::id().as_ref(),
+            did.as_ref(),
+            index.to_le_bytes().as_ref(),
+        ],
+        &id(),
+    )
+}
+
+/// Derive the transaction account proposed with `propose_sequenced_transaction`
+/// for `cryptid_account` with sequence number `sequence`
+pub fn derive_transaction_account(cryptid_account: &Pubkey, sequence: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            TransactionAccount::SEED_PREFIX,
+            cryptid_account.as_ref(),
+            sequence.to_le_bytes().as_ref(),
+        ],
+        &id(),
+    )
+}
+
+/// The addresses and bumps identifying a cryptid account
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CryptidAddress {
+    /// The DID account
+    pub did: Pubkey,
+    /// The bump seed for the DID account
+    pub did_account_bump: u8,
+    /// The cryptid account
+    pub cryptid_account: Pubkey,
+    /// The bump seed for the cryptid account
+    pub cryptid_account_bump: u8,
+    /// The index of the cryptid account
+    pub index: u32,
+}
+impl CryptidAddress {
+    /// The cryptid account with index `index` for `did:sol:<did_authority>`
+    pub fn new(did_authority: &Pubkey, index: u32) -> Self {
+        let (did, did_account_bump) = derive_did_account(did_authority);
+        let (cryptid_account, cryptid_account_bump) = derive_cryptid_account(&did, index);
+        Self {
+            did,
+            did_account_bump,
+            cryptid_account,
+            cryptid_account_bump,
+            index,
+        }
+    }
+
+    /// The named accounts that start every account list, in the order expected by `AllAccounts`
+    fn named_accounts(&self, authority: &Pubkey) -> [Pubkey; NAMED_ACCOUNTS_LEN] {
+        [self.cryptid_account, self.did, SolDID::id(), *authority]
+    }
+}
+
+/// The accounts referenced by a set of instructions, in the order expected by `AllAccounts`,
+/// along with the instructions abbreviated to reference them by index
+pub(crate) struct CompiledInstructions {
+    /// The abbreviated instructions, referencing the named accounts followed by the remaining accounts by index
+    pub instructions: Vec<AbbreviatedInstructionData>,
+    /// The privileges of the accounts after the named accounts, to be passed as remaining accounts
+    pub remaining_accounts: Vec<AccountMeta>,
+}
+impl CompiledInstructions {
+    /// Deduplicate the accounts of `instructions` after the named accounts of `address`,
+    /// and abbreviate the instructions to reference them by index
+    pub fn compile(
+        address: &CryptidAddress,
+        authority: &Pubkey,
+        instructions: Vec<Instruction>,
+    ) -> Result<Self> {
+        let mut keys = address.named_accounts(authority).to_vec();
+        let mut remaining_accounts: Vec<AccountMeta> = vec![];
+        for instruction in instructions.iter() {
+            let metas = instruction
+                .accounts
+                .iter()
+                .cloned()
+                .chain([AccountMeta::new_readonly(instruction.program_id, false)]);
+            for meta in metas {
+                // the cryptid account signs through the program, so is never a transaction signer
+                let is_signer = meta.is_signer && meta.pubkey != address.cryptid_account;
+                if let Some(index) = keys.iter().position(|key| *key == meta.pubkey) {
+                    // privileges of the named accounts are fixed by the cryptid instruction
+                    if let Some(existing) = index
+                        .checked_sub(NAMED_ACCOUNTS_LEN)
+                        .map(|index| &mut remaining_accounts[index])
+                    {
+                        existing.is_signer |= is_signer;
+                        existing.is_writable |= meta.is_writable;
+                    }
+                } else {
+                    keys.push(meta.pubkey);
+                    remaining_accounts.push(AccountMeta {
+                        pubkey: meta.pubkey,
+                        is_signer,
+                        is_writable: meta.is_writable,
+                    });
+                }
+            }
+        }
+
+        let indices = keys
+            .iter()
+            .enumerate()
+            .map(|(index, key)| {
+                u8::try_from(index)
+                    .map(|index| (*key, index))
+                    .map_err(|_| error!(CryptidError::IndexOutOfRange))
+            })
+            .collect::<Result<HashMap<_, _>>>()?;
+        let instructions = instructions
+            .into_iter()
+            .map(|instruction| {
+                AbbreviatedInstructionData::try_from_instruction(instruction, &indices)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            instructions,
+            remaining_accounts,
+        })
+    }
+}
+
+/// Build a `create_cryptid_account` instruction for the cryptid account at `address`, paid for by `authority`
+pub fn create_cryptid_account(
+    address: &CryptidAddress,
+    authority: &Pubkey,
+    middlewares: Vec<Pubkey>,
+    superuser_middlewares: Vec<Pubkey>,
+) -> Instruction {
+    Instruction {
+        program_id: id(),
+        accounts: crate::accounts::CreateCryptidAccount {
+            cryptid_account: address.cryptid_account,
+            did_program: SolDID::id(),
+            did: address.did,
+            authority: *authority,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::CreateCryptidAccount {
+            middlewares,
+            superuser_middlewares,
+            controller_chain: vec![],
+            index: address.index,
+            did_account_bump: address.did_account_bump,
+        }
+        .data(),
+    }
+}
+
+/// Build a `direct_execute` instruction executing `instructions` with the cryptid account at `address`
+pub fn direct_execute(
+    address: &CryptidAddress,
+    authority: &Pubkey,
+    instructions: Vec<Instruction>,
+    flags: u8,
+) -> Result<Instruction> {
+    let compiled = CompiledInstructions::compile(address, authority, instructions)?;
+    let mut accounts = crate::accounts::DirectExecute {
+        cryptid_account: address.cryptid_account,
+        did: address.did,
+        did_program: SolDID::id(),
+        authority: *authority,
+    }
+    .to_account_metas(None);
+    accounts.extend(compiled.remaining_accounts);
+    Ok(Instruction {
+        program_id: id(),
+        accounts,
+        data: crate::instruction::DirectExecute {
+            controller_chain: vec![],
+            instructions: compiled.instructions,
+            cryptid_account_bump: address.cryptid_account_bump,
+            cryptid_account_index: address.index,
+            did_account_bump: address.did_account_bump,
+            flags,
+        }
+        .data(),
+    })
+}
+
+/// Build a `propose_transaction` instruction proposing `instructions` at `transaction_account`.
+/// `transaction_account` is created by the instruction, so must also sign the transaction.
+pub fn propose_transaction(
+    address: &CryptidAddress,
+    authority: &Pubkey,
+    transaction_account: &Pubkey,
+    instructions: Vec<Instruction>,
+    state: TransactionState,
+    allow_unauthorized: bool,
+    expiry: Option<TransactionExpiry>,
+    flags: u8,
+) -> Result<Instruction> {
+    let compiled = CompiledInstructions::compile(address, authority, instructions)?;
+    propose_compiled_transaction(
+        address,
+        authority,
+        transaction_account,
+        compiled,
+        state,
+        allow_unauthorized,
+        expiry,
+        flags,
+    )
+}
+
+pub(crate) fn propose_compiled_transaction(
+    address: &CryptidAddress,
+    authority: &Pubkey,
+    transaction_account: &Pubkey,
+    compiled: CompiledInstructions,
+    state: TransactionState,
+    allow_unauthorized: bool,
+    expiry: Option<TransactionExpiry>,
+    flags: u8,
+) -> Result<Instruction> {
+    // the named accounts are already allowed for by `TransactionAccount::calculate_size`
+    let num_accounts = u8::try_from(compiled.remaining_accounts.len())
+        .map_err(|_| error!(CryptidError::IndexOutOfRange))?;
+    let mut accounts = crate::accounts::ProposeTransaction {
+        cryptid_account: address.cryptid_account,
+        did: address.did,
+        did_program: SolDID::id(),
+        authority: *authority,
+        transaction_account: *transaction_account,
+        system_program: system_program::ID,
+    }
+    .to_account_metas(None);
+    accounts.extend(compiled.remaining_accounts);
+    Ok(Instruction {
+        program_id: id(),
+        accounts,
+        data: crate::instruction::ProposeTransaction {
+            controller_chain: vec![],
+            cryptid_account_bump: address.cryptid_account_bump,
+            cryptid_account_index: address.index,
+            did_account_bump: address.did_account_bump,
+            state,
+            allow_unauthorized,
+            expiry,
+            flags,
+            instructions: compiled.instructions,
+            _num_accounts: num_accounts,
+        }
+        .data(),
+    })
+}
+
+/// Build an `extend_transaction` instruction adding `instructions` to `transaction_account`
+pub fn extend_transaction(
+    address: &CryptidAddress,
+    authority: &Pubkey,
+    transaction_account: &Pubkey,
+    instructions: Vec<Instruction>,
+    state: TransactionState,
+) -> Result<Instruction> {
+    let compiled = CompiledInstructions::compile(address, authority, instructions)?;
+    extend_compiled_transaction(address, authority, transaction_account, compiled, state)
+}
+
+pub(crate) fn extend_compiled_transaction(
+    address: &CryptidAddress,
+    authority: &Pubkey,
+    transaction_account: &Pubkey,
+    compiled: CompiledInstructions,
+    state: TransactionState,
+) -> Result<Instruction> {
+    // the named accounts are already allowed for by `TransactionAccount::calculate_size`
+    let num_accounts = u8::try_from(compiled.remaining_accounts.len())
+        .map_err(|_| error!(CryptidError::IndexOutOfRange))?;
+    let mut accounts = crate::accounts::ExtendTransaction {
+        cryptid_account: address.cryptid_account,
+        did: address.did,
+        did_program: SolDID::id(),
+        authority: *authority,
+        transaction_account: *transaction_account,
+        system_program: system_program::ID,
+    }
+    .to_account_metas(None);
+    accounts.extend(compiled.remaining_accounts);
+    Ok(Instruction {
+        program_id: id(),
+        accounts,
+        data: crate::instruction::ExtendTransaction {
+            controller_chain: vec![],
+            cryptid_account_bump: address.cryptid_account_bump,
+            cryptid_account_index: address.index,
+            did_account_bump: address.did_account_bump,
+            state,
+            instructions: compiled.instructions,
+            _num_accounts: num_accounts,
+        }
+        .data(),
+    })
+}
+
+/// Build an `execute_transaction` instruction for `transaction_account`, whose (deserialized) contents are `transaction`.
+/// The rent is refunded to the payer of the transaction account.
+pub fn execute_transaction(
+    address: &CryptidAddress,
+    authority: &Pubkey,
+    transaction_account: &Pubkey,
+    transaction: &TransactionAccount,
+    flags: u8,
+) -> Result<Instruction> {
+    let mut accounts = crate::accounts::ExecuteTransaction {
+        cryptid_account: address.cryptid_account,
+        did: address.did,
+        did_program: SolDID::id(),
+        authority: *authority,
+        destination: transaction.payer,
+        transaction_account: *transaction_account,
+    }
+    .to_account_metas(None);
+    accounts.extend(transaction_remaining_accounts(transaction)?);
+    Ok(Instruction {
+        program_id: id(),
+        accounts,
+        data: crate::instruction::ExecuteTransaction {
+            controller_chain: vec![],
+            cryptid_account_bump: address.cryptid_account_bump,
+            cryptid_account_index: address.index,
+            did_account_bump: address.did_account_bump,
+            flags,
+        }
+        .data(),
+    })
+}
+
+/// Build an `execute_transaction_chunk` instruction, executing up to `count` instructions
+/// of the non-atomic `transaction`, from where the previous chunk left off.
+/// The rent is refunded to the payer of the transaction account once the last chunk is executed.
+pub fn execute_transaction_chunk(
+    address: &CryptidAddress,
+    authority: &Pubkey,
+    transaction_account: &Pubkey,
+    transaction: &TransactionAccount,
+    flags: u8,
+    count: u8,
+) -> Result<Instruction> {
+    let mut accounts = crate::accounts::ExecuteTransactionChunk {
+        cryptid_account: address.cryptid_account,
+        did: address.did,
+        did_program: SolDID::id(),
+        authority: *authority,
+        destination: transaction.payer,
+        transaction_account: *transaction_account,
+    }
+    .to_account_metas(None);
+    accounts.extend(transaction_remaining_accounts(transaction)?);
+    Ok(Instruction {
+        program_id: id(),
+        accounts,
+        data: crate::instruction::ExecuteTransactionChunk {
+            controller_chain: vec![],
+            cryptid_account_bump: address.cryptid_account_bump,
+            cryptid_account_index: address.index,
+            did_account_bump: address.did_account_bump,
+            flags,
+            count,
+        }
+        .data(),
+    })
+}
+
+/// The remaining accounts to pass when executing `transaction`,
+/// with the privileges required by its instructions
+fn transaction_remaining_accounts(transaction: &TransactionAccount) -> Result<Vec<AccountMeta>> {
+    let mut remaining_accounts: Vec<AccountMeta> = transaction
+        .accounts
+        .iter()
+        .skip(NAMED_ACCOUNTS_LEN)
+        .map(|key| AccountMeta::new_readonly(*key, false))
+        .collect();
+    for instruction in transaction.instructions.iter() {
+        for meta in instruction.accounts.iter() {
+            let props = meta.props()?;
+            if let Some(account) = (meta.key as usize)
+                .checked_sub(NAMED_ACCOUNTS_LEN)
+                .and_then(|index| remaining_accounts.get_mut(index))
+            {
+                account.is_signer |= props.contains(AccountMetaProps::IS_SIGNER);
+                account.is_writable |= props.contains(AccountMetaProps::IS_WRITABLE);
+            }
+        }
+    }
+    Ok(remaining_accounts)
+}
+
+/// Build a `close_transaction` instruction for `transaction_account`, refunding the rent to `destination`
+pub fn close_transaction(
+    address: &CryptidAddress,
+    authority: &Pubkey,
+    transaction_account: &Pubkey,
+    destination: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: id(),
+        accounts: crate::accounts::CloseTransaction {
+            cryptid_account: address.cryptid_account,
+            did: address.did,
+            did_program: SolDID::id(),
+            authority: *authority,
+            destination: *destination,
+            transaction_account: *transaction_account,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::CloseTransaction {
+            controller_chain: vec![],
+            cryptid_account_bump: address.cryptid_account_bump,
+            cryptid_account_index: address.index,
+            did_account_bump: address.did_account_bump,
+        }
+        .data(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::state::instruction_size::InstructionSize;
+    use anchor_lang::solana_program::system_instruction;
+
+    #[test]
+    fn compile_instructions() {
+        let authority = Pubkey::new_unique();
+        let address = CryptidAddress::new(&authority, 0);
+        let recipient = Pubkey::new_unique();
+        let instructions = vec![
+            system_instruction::transfer(&address.cryptid_account, &recipient, 1),
+            system_instruction::transfer(&address.cryptid_account, &recipient, 2),
+        ];
+
+        let compiled = CompiledInstructions::compile(&address, &authority, instructions).unwrap();
+
+        // the recipient and system program are appended once, after the named accounts
+        assert_eq!(
+            compiled.remaining_accounts,
+            vec![
+                AccountMeta::new(recipient, false),
+                AccountMeta::new_readonly(system_program::ID, false)
+            ]
+        );
+        for instruction in compiled.instructions.iter() {
+            assert_eq!(instruction.program_id, 5);
+            assert_eq!(
+                instruction
+                    .accounts
+                    .iter()
+                    .map(|meta| (meta.key, meta.meta))
+                    .collect::<Vec<_>>(),
+                vec![(0, 3), (4, 2)]
+            );
+        }
+    }
+
+    #[test]
+    fn propose_transaction_requests_serialized_size() {
+        let authority = Pubkey::new_unique();
+        let address = CryptidAddress::new(&authority, 0);
+        let recipient = Pubkey::new_unique();
+        let instructions = vec![
+            system_instruction::transfer(&address.cryptid_account, &recipient, 1),
+            system_instruction::transfer(&address.cryptid_account, &recipient, 2),
+        ];
+        let compiled =
+            CompiledInstructions::compile(&address, &authority, instructions.clone()).unwrap();
+
+        let instruction = propose_transaction(
+            &address,
+            &authority,
+            &Pubkey::new_unique(),
+            instructions,
+            TransactionState::Ready,
+            true,
+            Some(TransactionExpiry::Slot(0)),
+            0,
+        )
+        .unwrap();
+        let data =
+            crate::instruction::ProposeTransaction::try_from_slice(&instruction.data[8..]).unwrap();
+        let requested_size = TransactionAccount::calculate_size(
+            data._num_accounts.into(),
+            InstructionSize::from_iter_to_iter(data.instructions.iter()),
+            0,
+            0,
+        );
+
+        // the largest account the proposal can produce, with every optional field set
+        let account = TransactionAccount {
+            cryptid_account: address.cryptid_account,
+            did: address.did,
+            accounts: address
+                .named_accounts(&authority)
+                .into_iter()
+                .chain(compiled.remaining_accounts.iter().map(|meta| meta.pubkey))
+                .collect(),
+            instructions: data.instructions,
+            approved_middlewares: vec![],
+            slot: 0,
+            expiry: data.expiry,
+            state: TransactionState::Executing { next_index: 0 },
+            unauthorized_signer: Some(authority),
+            authorized: false,
+            approvers: vec![],
+            payer: authority,
+            flags: data.flags,
+            sequence: Some(0),
+        };
+        let mut serialized = vec![];
+        account.try_serialize(&mut serialized).unwrap();
+        assert_eq!(requested_size, serialized.len());
+    }
+
+    #[test]
+    fn direct_execute_account_order() {
+        let authority = Pubkey::new_unique();
+        let address = CryptidAddress::new(&authority, 0);
+        let recipient = Pubkey::new_unique();
+
+        let instruction = direct_execute(
+            &address,
+            &authority,
+            vec![system_instruction::transfer(
+                &address.cryptid_account,
+                &recipient,
+                1,
+            )],
+            0,
+        )
+        .unwrap();
+
+        let keys: Vec<Pubkey> = instruction
+            .accounts
+            .iter()
+            .map(|meta| meta.pubkey)
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                address.cryptid_account,
+                address.did,
+                SolDID::id(),
+                authority,
+                recipient,
+                system_program::ID
+            ]
+        );
+        assert!(instruction.accounts[3].is_signer);
+        assert!(!instruction.accounts[0].is_signer);
+    }
+}
@@ -61,4 +61,108 @@ pub enum CryptidError {
     /// Already authorized Transaction Account.
     #[msg("Transaction Account is already authorized and cannot be authorized again.")]
     AlreadyAuthorizedTransactionAccount,
+    /// The cryptid account still has open transaction accounts.
+    #[msg("The cryptid account cannot be closed while it has open transaction accounts.")]
+    OpenTransactionAccounts,
+    /// Direct execution was attempted on a cryptid account with a middleware.
+    #[msg("Cryptid accounts with middleware cannot use direct execution. Propose the transaction instead.")]
+    DirectExecuteWithMiddleware,
+    /// The threshold of a cryptid account must be at least one.
+    #[msg("The threshold of a cryptid account must be at least one.")]
+    InvalidThreshold,
+    /// The authority has already approved the transaction.
+    #[msg("The authority has already approved the transaction.")]
+    AlreadyApproved,
+    /// The transaction has passed its expiry.
+    #[msg("The transaction has expired.")]
+    TransactionExpired,
+    /// The transaction account can only be reclaimed once it has expired or been abandoned.
+    #[msg("The transaction account has not expired or been open long enough to be reclaimed.")]
+    TransactionNotReclaimable,
+    /// The rent destination is not the payer of the transaction account.
+    #[msg("The rent for the transaction account can only be refunded to its payer.")]
+    InvalidRentDestination,
+    /// Sequenced transactions require a non-generative cryptid account.
+    #[msg("Sequenced transactions can only be proposed for non-generative cryptid accounts.")]
+    SequenceOnGenerativeAccount,
+    /// The sequence number is not the next sequence number of the cryptid account.
+    #[msg("The transaction sequence number does not match the cryptid account.")]
+    InvalidTransactionSequence,
+    /// The account meta contains unknown flags.
+    #[msg("The account meta contains unknown flags.")]
+    InvalidAccountMeta,
+    /// An instruction references an account that is not in the list of accounts.
+    #[msg("An instruction references an account that was not provided.")]
+    MissingInstructionAccount,
+    /// The number of accounts passed to execute does not match the transaction account.
+    #[msg(
+        "The number of accounts passed to execute does not match those in the transaction account."
+    )]
+    InvalidAccountCount,
+    /// Only transactions proposed with the NON_ATOMIC flag can be executed in chunks.
+    #[msg("Only transactions proposed as non-atomic can be executed in chunks.")]
+    AtomicTransaction,
+    /// A chunk must execute at least one instruction.
+    #[msg("A chunk must execute at least one instruction.")]
+    InvalidChunkSize,
+    /// The account to be created already holds lamports.
+    #[msg("The account to be created is already in use.")]
+    AccountAlreadyInUse,
+    /// The cryptid account would fall below its rent-exempt minimum balance.
+    #[msg("The cryptid account must remain rent-exempt.")]
+    CryptidAccountNotRentExempt,
+    /// The cryptid account was reassigned or resized by a sub-instruction.
+    #[msg("The cryptid account was modified by a sub-instruction.")]
+    CryptidAccountModified,
+    /// An instruction invokes the cryptid program itself.
+    #[msg("Instructions executed by cryptid cannot invoke the cryptid program.")]
+    ReentrantInstruction,
+    /// An instruction requires an account to sign that can never be a signer.
+    #[msg("An instruction requires a signature from an account that cannot sign.")]
+    UnsatisfiableSigner,
+    /// An instruction requires an account to be writable that can never be writable.
+    #[msg("An instruction requires an account that cannot be written to be writable.")]
+    UnsatisfiableWritable,
+    /// The signer is not the upgrade authority of the cryptid program.
+    #[msg("The signer is not the upgrade authority of the cryptid program.")]
+    NotUpgradeAuthority,
+    /// The signer is not the authority of the approval registry.
+    #[msg("The signer is not the authority of the approval registry.")]
+    NotRegistryAuthority,
+    /// The middleware account is not owned by a program in the approval registry.
+    #[msg("The middleware account is not owned by a registered middleware program.")]
+    UnregisteredMiddleware,
+    /// A middleware appears more than once in the middleware pipeline.
+    #[msg("A middleware cannot appear more than once in the middleware pipeline.")]
+    DuplicateMiddleware,
+    /// The middleware is not in the middleware pipeline of the cryptid account.
+    #[msg("The middleware is not in the middleware pipeline of the cryptid account.")]
+    MiddlewareNotInPipeline,
+    /// A non-atomic transaction holds more instructions than its execution progress can track.
+    #[msg("A non-atomic transaction cannot hold this many instructions.")]
+    TooManyInstructions,
+    /// Unknown flags were passed to an execution instruction.
+    #[msg("Unknown execute flags.")]
+    InvalidExecuteFlags,
+    /// A sequenced transaction was executed or closed before the transactions proposed ahead of it.
+    #[msg("Sequenced transactions must be executed or closed in the order in which they were proposed.")]
+    OutOfOrderTransaction,
+    /// The transaction account has no space reserved for another middleware approval,
+    /// e.g. because the middleware pipeline was lengthened after the transaction was proposed.
+    #[msg("The transaction account has no space for another middleware approval. Propose the transaction again.")]
+    NoSpaceForMiddlewareApproval,
+    /// The account is not a cryptid account in the legacy layout, so cannot be migrated.
+    #[msg("The cryptid account is not in the legacy layout.")]
+    NotLegacyCryptidAccount,
+    /// The cryptid program has no upgrade authority to initialize the approval registry.
+    #[msg(
+        "The cryptid program is immutable, so the approval registry can no longer be initialized."
+    )]
+    ImmutableCryptidProgram,
+    /// The middleware of the cryptid account did not sign a change to the cryptid account.
+    #[msg("The middleware of the cryptid account must approve changes to it.")]
+    MiddlewareApprovalRequired,
+    /// A non-authority on the DID attempted to propose a sequenced transaction.
+    #[msg("Sequenced transactions can only be proposed by an authority on the DID.")]
+    UnauthorizedSequencedTransaction,
 }
@@ -0,0 +1,147 @@
+//! Events emitted by the cryptid program at each step of the lifecycle of
+//! cryptid accounts and transaction accounts.
+
+use crate::state::abbreviated_instruction_data::AbbreviatedInstructionData;
+use crate::state::transaction_state::TransactionState;
+use anchor_lang::prelude::*;
+
+/// A non-generative cryptid account was created
+#[event]
+pub struct CryptidAccountCreated {
+    pub cryptid_account: Pubkey,
+    pub did: Pubkey,
+    /// The DID authority that created the account
+    pub authority: Pubkey,
+    pub index: u32,
+}
+
+/// The middlewares, threshold or reclaim age of a non-generative cryptid account were updated
+#[event]
+pub struct CryptidAccountUpdated {
+    pub cryptid_account: Pubkey,
+    pub did: Pubkey,
+    /// The DID authority that updated the account
+    pub authority: Pubkey,
+}
+
+/// A non-generative cryptid account was closed
+#[event]
+pub struct CryptidAccountClosed {
+    pub cryptid_account: Pubkey,
+    pub did: Pubkey,
+    /// The DID authority that closed the account
+    pub authority: Pubkey,
+    /// The account that received the lamports of the cryptid account
+    pub destination: Pubkey,
+}
+
+/// A transaction was proposed for a cryptid account
+#[event]
+pub struct TransactionProposed {
+    pub cryptid_account: Pubkey,
+    pub did: Pubkey,
+    pub transaction_account: Pubkey,
+    /// The signer that proposed the transaction
+    pub authority: Pubkey,
+    pub state: TransactionState,
+    /// The sequence number of the transaction, if proposed with `propose_sequenced_transaction`
+    pub sequence: Option<u64>,
+}
+
+/// Instructions were added to a proposed transaction
+#[event]
+pub struct TransactionExtended {
+    pub cryptid_account: Pubkey,
+    pub did: Pubkey,
+    pub transaction_account: Pubkey,
+    /// The signer that extended the transaction
+    pub authority: Pubkey,
+    /// The number of instructions added
+    pub instruction_count: u16,
+}
+
+/// The state of a proposed transaction changed
+#[event]
+pub struct TransactionStateChanged {
+    pub cryptid_account: Pubkey,
+    pub did: Pubkey,
+    pub transaction_account: Pubkey,
+    /// The signer that changed the state
+    pub authority: Pubkey,
+    pub previous_state: TransactionState,
+    pub state: TransactionState,
+}
+
+/// A DID authority approved a proposed transaction, counting towards the threshold
+#[event]
+pub struct TransactionApproved {
+    pub cryptid_account: Pubkey,
+    pub did: Pubkey,
+    pub transaction_account: Pubkey,
+    /// The DID authority that approved the transaction
+    pub authority: Pubkey,
+}
+
+/// A middleware approved the execution of a proposed transaction
+#[event]
+pub struct MiddlewareApproved {
+    pub cryptid_account: Pubkey,
+    pub did: Pubkey,
+    pub transaction_account: Pubkey,
+    /// The middleware account that approved the transaction
+    pub middleware_account: Pubkey,
+    /// The program that owns the middleware account
+    pub middleware_program: Pubkey,
+}
+
+/// A superuser middleware approved the execution of a transaction proposed by an unauthorized signer
+#[event]
+pub struct SuperuserApproved {
+    pub cryptid_account: Pubkey,
+    pub did: Pubkey,
+    pub transaction_account: Pubkey,
+    /// The middleware account that approved the transaction
+    pub middleware_account: Pubkey,
+    /// The program that owns the middleware account
+    pub middleware_program: Pubkey,
+    /// True if this approval authorized the transaction (i.e. it was from the last superuser middleware)
+    pub authorized: bool,
+}
+
+/// Instructions were executed by a cryptid account
+#[event]
+pub struct TransactionExecuted {
+    pub cryptid_account: Pubkey,
+    pub did: Pubkey,
+    /// The transaction account that was executed, or None for `direct_execute`
+    pub transaction_account: Option<Pubkey>,
+    /// The signer that executed the transaction
+    pub authority: Pubkey,
+    /// The program ids of the executed instructions, in order
+    pub program_ids: Vec<Pubkey>,
+}
+impl TransactionExecuted {
+    /// Resolve the program ids of `instructions` against the accounts they reference by index
+    pub fn program_ids(
+        instructions: &[AbbreviatedInstructionData],
+        accounts: &[Pubkey],
+    ) -> Vec<Pubkey> {
+        instructions
+            .iter()
+            .filter_map(|instruction| accounts.get(instruction.program_id as usize))
+            .cloned()
+            .collect()
+    }
+}
+
+/// A transaction account was closed without being executed
+#[event]
+pub struct TransactionClosed {
+    pub cryptid_account: Pubkey,
+    pub did: Pubkey,
+    pub transaction_account: Pubkey,
+    /// The signer that closed the transaction, or None if it was reclaimed permissionlessly
+    pub authority: Option<Pubkey>,
+    /// The account that received the rent
+    pub destination: Pubkey,
+}
@@ -1,38 +1,66 @@
 use crate::error::CryptidError;
+use crate::events::MiddlewareApproved;
+use crate::state::approval_registry::ApprovalRegistry;
+use crate::state::cryptid_account::CryptidAccount;
 use crate::state::transaction_account::TransactionAccount;
 use crate::state::transaction_state::TransactionState;
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
 pub struct ApproveExecution<'info> {
+    /// The middleware account approving the transaction.
+    /// Must be owned by a program in the approval registry, and be in the middleware pipeline of the cryptid account
+    #[account(
+        constraint = approval_registry.is_registered(middleware_account.owner) @ CryptidError::UnregisteredMiddleware,
+        constraint = cryptid_account.middlewares.contains(middleware_account.key) @ CryptidError::MiddlewareNotInPipeline,
+    )]
     pub middleware_account: Signer<'info>,
     #[account(
         mut,
         // ensure the transaction is not approved until it is ready to be approved, and is not executed
         constraint = transaction_account.state == TransactionState::Ready @ CryptidError::InvalidTransactionState,
+        has_one = cryptid_account @ CryptidError::WrongCryptidAccount,
     )]
     pub transaction_account: Account<'info, TransactionAccount>,
+    /// The registry of middleware programs that can approve transactions
+    #[account(
+        seeds = [ApprovalRegistry::SEED_PREFIX],
+        bump,
+    )]
+    pub approval_registry: Account<'info, ApprovalRegistry>,
+    /// The cryptid account that the transaction was proposed for
+    pub cryptid_account: Account<'info, CryptidAccount>,
 }
 
 /// Executes a transaction directly if all required keys sign
 pub fn approve_execution<'info>(
     ctx: Context<'_, '_, '_, 'info, ApproveExecution<'info>>,
 ) -> Result<()> {
-    // Make sure owner is NOT the System Program
-    // TODO(ticket): Consider implementing an approval registry on middleware
-    require!(
-        !anchor_lang::solana_program::system_program::check_id(
-            ctx.accounts.middleware_account.owner
-        ),
-        CryptidError::InvalidMiddlewareAccount
-    );
+    ctx.accounts.transaction_account.check_not_expired()?;
 
     msg!(
         "Transaction approved by middleware owned by program: {}",
         ctx.accounts.middleware_account.owner
     );
-    ctx.accounts.transaction_account.approved_middleware =
-        Some(*ctx.accounts.middleware_account.key);
+    // middlewares approve in the order of the cryptid account's pipeline
+    let data_len = ctx
+        .accounts
+        .transaction_account
+        .to_account_info()
+        .data_len();
+    ctx.accounts.transaction_account.approve_middleware(
+        ctx.accounts.middleware_account.key,
+        &ctx.accounts.cryptid_account.middlewares,
+        data_len,
+    )?;
+
+    emit!(MiddlewareApproved {
+        cryptid_account: ctx.accounts.transaction_account.cryptid_account,
+        did: ctx.accounts.transaction_account.did,
+        transaction_account: ctx.accounts.transaction_account.key(),
+        middleware_account: ctx.accounts.middleware_account.key(),
+        middleware_program: *ctx.accounts.middleware_account.owner,
+    });
 
     Ok(())
 }
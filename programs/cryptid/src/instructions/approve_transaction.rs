@@ -0,0 +1,118 @@
+use crate::error::CryptidError;
+use crate::events::TransactionApproved;
+use crate::instructions::util::*;
+use crate::state::did_reference::DIDReference;
+use crate::state::transaction_account::TransactionAccount;
+use crate::util::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(
+/// A vector of controller account indices and their associated DID authority keys (to allow for generative cases).
+controller_chain: Vec<DIDReference>,
+/// The bump seed for the Cryptid signer
+cryptid_account_bump: u8,
+/// Index of the cryptid account
+cryptid_account_index: u32,
+/// The bump seed for the Did Account
+did_account_bump: u8,
+)]
+pub struct ApproveTransaction<'info> {
+    /// The Cryptid instance that can execute the transaction.
+    /// CHECK: Cryptid Account can be generative and non-generative
+    #[account(
+    // TODO(ticket): Verification done in instruction body. Move back with Anchor generator
+    // seeds = [CryptidAccount::SEED_PREFIX, did_program.key().as_ref(), did.key().as_ref(), cryptid_account_index.to_le_bytes().as_ref()],
+    // bump = cryptid_account_bump
+    )]
+    pub cryptid_account: UncheckedAccount<'info>,
+    /// The DID on the Cryptid instance
+    /// CHECK: DID Account can be generative or not
+    pub did: UncheckedAccount<'info>,
+    /// The program for the DID
+    pub did_program: Program<'info, SolDID>,
+    /// The DID authority approving the transaction
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// The transaction to approve
+    #[account(
+    mut,
+    has_one = cryptid_account @ CryptidError::WrongCryptidAccount,
+    // transactions that have started executing, or have executed, cannot be approved
+    constraint = transaction_account.state.is_pending() @ CryptidError::InvalidTransactionState,
+    // make space for the new approver
+    realloc = transaction_account.to_account_info().data_len() + 32,
+    realloc::payer = authority,
+    realloc::zero = false,
+    )]
+    pub transaction_account: Account<'info, TransactionAccount>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Collect all accounts as a single vector so that they can be referenced by index by the controller chain
+impl<'a, 'b, 'c, 'info> AllAccounts<'a, 'b, 'c, 'info>
+    for Context<'a, 'b, 'c, 'info, ApproveTransaction<'info>>
+{
+    fn all_accounts(&self) -> Vec<&AccountInfo<'info>> {
+        [
+            self.accounts.cryptid_account.as_ref(),
+            self.accounts.did.as_ref(),
+            self.accounts.did_program.as_ref(),
+            self.accounts.authority.as_ref(),
+        ]
+        .into_iter()
+        .chain(self.remaining_accounts.iter())
+        .collect()
+    }
+
+    fn get_accounts_by_indexes(&self, indexes: &[u8]) -> Result<Vec<&AccountInfo<'info>>> {
+        let accounts = self.all_accounts();
+        resolve_by_index(indexes, &accounts)
+    }
+}
+
+/// Record the approval of a DID authority on a transaction.
+/// Approvals count towards the threshold of the cryptid account when the transaction is executed.
+pub fn approve_transaction<'info>(
+    ctx: Context<'_, '_, '_, 'info, ApproveTransaction<'info>>,
+    controller_chain: Vec<DIDReference>,
+    cryptid_account_bump: u8,
+    cryptid_account_index: u32,
+    did_account_bump: u8,
+) -> Result<()> {
+    let all_accounts = ctx.all_accounts();
+
+    get_cryptid_account_checked(
+        &all_accounts,
+        &controller_chain,
+        &ctx.accounts.cryptid_account,
+        &ctx.accounts.did,
+        &ctx.accounts.did_program,
+        &ctx.accounts.authority,
+        did_account_bump,
+        cryptid_account_index,
+        cryptid_account_bump,
+        false,
+    )?;
+
+    ctx.accounts.transaction_account.check_not_expired()?;
+
+    let authority = ctx.accounts.authority.key();
+    require!(
+        !ctx.accounts
+            .transaction_account
+            .approvers
+            .contains(&authority),
+        CryptidError::AlreadyApproved
+    );
+    ctx.accounts.transaction_account.approvers.push(authority);
+
+    emit!(TransactionApproved {
+        cryptid_account: ctx.accounts.cryptid_account.key(),
+        did: ctx.accounts.did.key(),
+        transaction_account: ctx.accounts.transaction_account.key(),
+        authority,
+    });
+
+    Ok(())
+}
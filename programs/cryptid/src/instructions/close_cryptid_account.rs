@@ -0,0 +1,124 @@
+use crate::error::CryptidError;
+use crate::events::CryptidAccountClosed;
+use crate::instructions::util::*;
+use crate::state::cryptid_account::CryptidAccount;
+use crate::state::did_reference::DIDReference;
+use crate::util::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(
+/// A vector of controller account indices and their associated DID authority keys (to allow for generative cases).
+controller_chain: Vec<DIDReference>,
+/// The bump seed for the Cryptid signer
+cryptid_account_bump: u8,
+/// Index of the cryptid account
+cryptid_account_index: u32,
+/// The bump seed for the Did Account
+did_account_bump: u8,
+)]
+pub struct CloseCryptidAccount<'info> {
+    /// The Cryptid instance to close
+    /// CHECK: Verification done in instruction body, as for the other instructions.
+    #[account(mut)]
+    pub cryptid_account: UncheckedAccount<'info>,
+    /// The DID on the Cryptid instance
+    /// CHECK: DID Account can be generative or not
+    pub did: UncheckedAccount<'info>,
+    /// The program for the DID
+    pub did_program: Program<'info, SolDID>,
+    /// The signer of the transaction. Must be a DID authority.
+    pub authority: Signer<'info>,
+    /// CHECK: Destination account does not need to satisfy any constraints.
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+}
+
+/// Collect all accounts as a single vector so that they can be referenced by index by the controller chain
+impl<'a, 'b, 'c, 'info> AllAccounts<'a, 'b, 'c, 'info>
+    for Context<'a, 'b, 'c, 'info, CloseCryptidAccount<'info>>
+{
+    fn all_accounts(&self) -> Vec<&AccountInfo<'info>> {
+        [
+            self.accounts.cryptid_account.as_ref(),
+            self.accounts.did.as_ref(),
+            self.accounts.did_program.as_ref(),
+            self.accounts.authority.as_ref(),
+        ]
+        .into_iter()
+        .chain(self.remaining_accounts.iter())
+        .collect()
+    }
+
+    fn get_accounts_by_indexes(&self, indexes: &[u8]) -> Result<Vec<&AccountInfo<'info>>> {
+        let accounts = self.all_accounts();
+        resolve_by_index(indexes, &accounts)
+    }
+}
+
+/// Close a non-generative cryptid account, sending all its lamports to the destination.
+/// As many DID authorities as the cryptid account's threshold must sign,
+/// the additional ones passed as signers in the remaining accounts.
+/// If the cryptid account has middleware, it must also sign (see [`CryptidAccount::check_middleware_signers`]).
+pub fn close_cryptid_account<'info>(
+    ctx: Context<'_, '_, '_, 'info, CloseCryptidAccount<'info>>,
+    controller_chain: Vec<DIDReference>,
+    cryptid_account_bump: u8,
+    cryptid_account_index: u32,
+    did_account_bump: u8,
+) -> Result<()> {
+    let all_accounts = ctx.all_accounts();
+
+    let cryptid_account = get_cryptid_account_checked(
+        &all_accounts,
+        &controller_chain,
+        &ctx.accounts.cryptid_account,
+        &ctx.accounts.did,
+        &ctx.accounts.did_program,
+        &ctx.accounts.authority,
+        did_account_bump,
+        cryptid_account_index,
+        cryptid_account_bump,
+        false,
+    )?;
+
+    // CHECK enough DID authorities have signed to close the account, as for executing a transaction
+    verify_threshold(
+        &all_accounts,
+        &resolve_controller_chain(&all_accounts, &controller_chain)?,
+        &ctx.accounts.did,
+        did_account_bump,
+        cryptid_account.threshold,
+        &[],
+    )?;
+
+    // CHECK the middleware approves closing the account, as it could otherwise be bypassed by sweeping its lamports
+    let signers: Vec<Pubkey> = ctx
+        .remaining_accounts
+        .iter()
+        .filter(|account| account.is_signer)
+        .map(|account| *account.key)
+        .collect();
+    cryptid_account.check_middleware_signers(&signers)?;
+
+    require_eq!(
+        cryptid_account.open_transactions,
+        0,
+        CryptidError::OpenTransactionAccounts
+    );
+
+    // Generative accounts are owned by the system program and cannot be closed,
+    // so this fails unless the account was created with create_cryptid_account
+    let cryptid_account: Account<CryptidAccount> =
+        Account::try_from(ctx.accounts.cryptid_account.as_ref())?;
+    cryptid_account.close(ctx.accounts.destination.to_account_info())?;
+
+    emit!(CryptidAccountClosed {
+        cryptid_account: ctx.accounts.cryptid_account.key(),
+        did: ctx.accounts.did.key(),
+        authority: ctx.accounts.authority.key(),
+        destination: ctx.accounts.destination.key(),
+    });
+
+    Ok(())
+}
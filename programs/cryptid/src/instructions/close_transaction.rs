@@ -1,4 +1,5 @@
 use crate::error::CryptidError;
+use crate::events::TransactionClosed;
 use crate::instructions::util::*;
 use crate::state::did_reference::DIDReference;
 use crate::state::transaction_account::TransactionAccount;
@@ -34,7 +35,7 @@ pub struct CloseTransaction<'info> {
     pub did_program: Program<'info, SolDID>,
     /// The signer of the transaction
     pub authority: Signer<'info>,
-    /// CHECK: Rent destination account does not need to satisfy the any constraints.
+    /// CHECK: Rent destination account is checked against the transaction account.
     #[account(mut)]
     pub destination: UncheckedAccount<'info>,
     /// The instruction to execute
@@ -42,7 +43,10 @@ pub struct CloseTransaction<'info> {
     mut,
     close = destination,
     has_one = cryptid_account @ CryptidError::WrongCryptidAccount,
+    // the rent is refunded to the payer, unless they opted out when proposing the transaction
+    constraint = transaction_account.is_valid_rent_destination(destination.key) @ CryptidError::InvalidRentDestination,
     // This Instruction only allows to recover transactions that were not executed.
+    // Transactions part-way through a non-atomic execution can be closed, abandoning their remaining instructions.
     constraint = transaction_account.state != TransactionState::Executed @ CryptidError::InvalidTransactionState,
     // if the transaction was created
     constraint = transaction_account.unauthorized_signer.unwrap_or_else(|| authority.key()) == authority.key() @ CryptidError::KeyMustBeSigner,
@@ -105,7 +109,7 @@ pub fn close_transaction<'info>(
         .unauthorized_signer
         .is_some();
 
-    get_cryptid_account_checked(
+    let mut cryptid_account = get_cryptid_account_checked(
         &all_accounts,
         &controller_chain,
         &ctx.accounts.cryptid_account,
@@ -118,5 +122,19 @@ pub fn close_transaction<'info>(
         allow_unauthorized_signer,
     )?;
 
-    Ok(())
+    emit!(TransactionClosed {
+        cryptid_account: ctx.accounts.cryptid_account.key(),
+        did: ctx.accounts.did.key(),
+        transaction_account: ctx.accounts.transaction_account.key(),
+        authority: Some(ctx.accounts.authority.key()),
+        destination: ctx.accounts.destination.key(),
+    });
+
+    // An expired sequenced transaction can be closed ahead of its turn
+    let transaction_account = &ctx.accounts.transaction_account;
+    cryptid_account.close_transaction(
+        transaction_account.sequence,
+        transaction_account.is_expired(&Clock::get()?),
+    )?;
+    cryptid_account.save(&ctx.accounts.cryptid_account)
 }
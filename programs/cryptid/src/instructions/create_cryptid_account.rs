@@ -1,4 +1,5 @@
 use crate::error::CryptidError;
+use crate::events::CryptidAccountCreated;
 use crate::instructions::util::*;
 use crate::state::cryptid_account::CryptidAccount;
 use crate::util::*;
@@ -6,8 +7,8 @@ use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
 #[instruction(
-/// Optional middleware to be associated with the cryptid account
-middleware: Option<Pubkey>,
+/// The middleware pipeline of the cryptid account, in approval order. Empty if the account has no middleware
+middlewares: Vec<Pubkey>,
 /// Registered "Superuser" middlewares, if any
 superuser_middlewares: Vec<Pubkey>,
 /// The controller chain between the authority and the did
@@ -21,7 +22,7 @@ pub struct CreateCryptidAccount<'info> {
     #[account(
     init,
     payer = authority,
-    space = 8 + CryptidAccount::calculate_size(superuser_middlewares.len()),
+    space = 8 + CryptidAccount::calculate_size(middlewares.len(), superuser_middlewares.len()),
     seeds = [CryptidAccount::SEED_PREFIX, did_program.key().as_ref(), did.key().as_ref(), index.to_le_bytes().as_ref()],
     bump,
     )]
@@ -39,16 +40,18 @@ pub struct CreateCryptidAccount<'info> {
 
 pub fn create_cryptid_account(
     ctx: Context<CreateCryptidAccount>,
-    middleware: Option<Pubkey>,
+    middlewares: Vec<Pubkey>,
     superuser_middlewares: Vec<Pubkey>,
     controller_chain: Vec<Pubkey>,
     index: u32,
     did_account_bump: u8,
 ) -> Result<()> {
     require_gt!(index, 0, CryptidError::CreatingWithZeroIndex);
-    ctx.accounts.cryptid_account.middleware = middleware;
+    CryptidAccount::check_middlewares(&middlewares)?;
+    ctx.accounts.cryptid_account.middlewares = middlewares;
     ctx.accounts.cryptid_account.index = index;
     ctx.accounts.cryptid_account.superuser_middleware = superuser_middlewares;
+    ctx.accounts.cryptid_account.threshold = 1;
 
     // convert the controller chain (an array of account indices) into an array of accounts
     // note - cryptid does not need to check that the chain is valid, or even that they are DIDs
@@ -69,5 +72,12 @@ pub fn create_cryptid_account(
         controlling_did_accounts,
     )?;
 
+    emit!(CryptidAccountCreated {
+        cryptid_account: ctx.accounts.cryptid_account.key(),
+        did: ctx.accounts.did.key(),
+        authority: ctx.accounts.authority.key(),
+        index,
+    });
+
     Ok(())
 }
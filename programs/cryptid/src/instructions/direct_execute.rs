@@ -1,3 +1,5 @@
+use crate::error::CryptidError;
+use crate::events::TransactionExecuted;
 use crate::instructions::util::*;
 use crate::state::abbreviated_instruction_data::AbbreviatedInstructionData;
 use crate::state::did_reference::DIDReference;
@@ -61,6 +63,7 @@ impl<'a, 'b, 'c, 'info> AllAccounts<'a, 'b, 'c, 'info>
 }
 
 /// Executes a transaction directly if all required keys sign
+/// Only allowed on cryptid accounts without middleware, as there is no opportunity for the middleware to approve it.
 pub fn direct_execute<'info>(
     ctx: Context<'_, '_, '_, 'info, DirectExecute<'info>>,
     controller_chain: Vec<DIDReference>,
@@ -70,9 +73,8 @@ pub fn direct_execute<'info>(
     did_account_bump: u8,
     flags: u8,
 ) -> Result<()> {
-    let debug = ExecuteFlags::from_bits(flags)
-        .unwrap()
-        .contains(ExecuteFlags::DEBUG);
+    let flags = ExecuteFlags::parse(flags)?;
+    let debug = flags.contains(ExecuteFlags::DEBUG);
     if debug {
         ctx.accounts.print_keys();
     }
@@ -92,18 +94,44 @@ pub fn direct_execute<'info>(
         false,
     )?;
 
+    // A middleware can only approve proposed transactions.
+    // Allowing direct execution would bypass it entirely.
+    require!(
+        cryptid_account.middlewares.is_empty(),
+        CryptidError::DirectExecuteWithMiddleware
+    );
+
+    verify_threshold(
+        &all_accounts,
+        &resolve_controller_chain(&all_accounts, &controller_chain)?,
+        &ctx.accounts.did,
+        did_account_bump,
+        cryptid_account.threshold,
+        &[],
+    )?;
+
     // At this point, we are safe that the authority is a valid owner of the cryptid account. We can execute the instructions
     CPI::execute_instructions(
         &instructions,
+        0,
         &ctx.all_accounts(),
         &ctx.accounts.did_program.key(),
         &ctx.accounts.did.key(),
         &cryptid_account,
         &ctx.accounts.cryptid_account.to_account_info(),
         cryptid_account_bump,
-        debug,
+        flags,
     )?;
 
+    let account_keys: Vec<Pubkey> = all_accounts.iter().map(|a| *a.key).collect();
+    emit!(TransactionExecuted {
+        cryptid_account: ctx.accounts.cryptid_account.key(),
+        did: ctx.accounts.did.key(),
+        transaction_account: None,
+        authority: ctx.accounts.authority.key(),
+        program_ids: TransactionExecuted::program_ids(&instructions, &account_keys),
+    });
+
     Ok(())
 }
 
@@ -1,4 +1,5 @@
 use crate::error::CryptidError;
+use crate::events::TransactionExecuted;
 use crate::instructions::util::*;
 use crate::state::did_reference::DIDReference;
 use crate::state::transaction_account::TransactionAccount;
@@ -37,7 +38,7 @@ pub struct ExecuteTransaction<'info> {
     pub did_program: Program<'info, SolDID>,
     /// The signer of the transaction
     pub authority: Signer<'info>,
-    /// CHECK: Rent destination account does not need to satisfy the any constraints.
+    /// CHECK: Rent destination account is checked against the transaction account.
     #[account(mut)]
     pub destination: UncheckedAccount<'info>,
     /// The instruction to execute
@@ -45,6 +46,8 @@ pub struct ExecuteTransaction<'info> {
     mut,
     close = destination,
     has_one = cryptid_account @ CryptidError::WrongCryptidAccount,
+    // the rent is refunded to the payer, unless they opted out when proposing the transaction
+    constraint = transaction_account.is_valid_rent_destination(destination.key) @ CryptidError::InvalidRentDestination,
     // safeguard to prevent double-spends in the case where the account is not closed for some reason
     // only "Ready" transactions can be executed
     constraint = transaction_account.state == TransactionState::Ready @ CryptidError::InvalidTransactionState,
@@ -53,9 +56,9 @@ pub struct ExecuteTransaction<'info> {
     constraint = transaction_account.authorized @ CryptidError::UnauthorizedTransaction,
     // if the transaction was created
     constraint = transaction_account.unauthorized_signer.unwrap_or_else(|| authority.key()) == authority.key() @ CryptidError::KeyMustBeSigner,
-    // the transaction account must have been approved by the middleware on the cryptid account, if present
+    // the transaction account must have been approved by the middlewares on the cryptid account, if present
     // TODO(ticket): Verification done in instruction body. Move back with Anchor generator
-    // constraint = transaction_account.approved_middleware == cryptid_account.middleware @ CryptidError::IncorrectMiddleware,
+    // constraint = transaction_account.check_middlewares_approved(&cryptid_account.middlewares).is_ok() @ CryptidError::IncorrectMiddleware,
     )]
     pub transaction_account: Account<'info, TransactionAccount>,
 }
@@ -94,9 +97,8 @@ pub fn execute_transaction<'info>(
     did_account_bump: u8,
     flags: u8,
 ) -> Result<()> {
-    let debug = ExecuteFlags::from_bits(flags)
-        .unwrap()
-        .contains(ExecuteFlags::DEBUG);
+    let flags = ExecuteFlags::parse(flags)?;
+    let debug = flags.contains(ExecuteFlags::DEBUG);
 
     // if there is an unauthorized signer, it must be the one executing the transaction
     // in this case, a superuser middleware must have authorized the transaction
@@ -129,7 +131,7 @@ pub fn execute_transaction<'info>(
         .unauthorized_signer
         .is_some();
 
-    let cryptid_account = get_cryptid_account_checked(
+    let mut cryptid_account = get_cryptid_account_checked(
         &all_accounts,
         &controller_chain,
         &ctx.accounts.cryptid_account,
@@ -142,30 +144,41 @@ pub fn execute_transaction<'info>(
         allow_unauthorized_signer,
     )?;
 
-    // CHECK the accounts have not been switched since the transaction was proposed
-    let account_pairs = all_accounts
-        .iter()
-        .enumerate()
-        .zip(ctx.accounts.transaction_account.accounts.iter());
-    for ((index, account), proposed_account) in account_pairs {
-        // The authority is allowed to change
-        // As long as the authority is valid for the DID (checked above), any authority can sign the transaction.
-        msg!("Checking account {} {:?}", index, account.key);
-        if index != AUTHORITY_ACCOUNT_INDEX {
-            require_keys_eq!(
-                *account.key,
-                *proposed_account,
-                CryptidError::InvalidAccounts
-            )
-        }
+    // CHECK the transaction has not expired since it was proposed
+    ctx.accounts.transaction_account.check_not_expired()?;
+
+    // CHECK the accounts have not been switched, added or removed since the transaction was proposed
+    // The authority is allowed to change
+    // As long as the authority is valid for the DID (checked above), any authority can sign the transaction.
+    // Additional DID authorities may follow them as signers, but are not passed to the instructions
+    let proposed_accounts =
+        check_transaction_accounts(&all_accounts, &ctx.accounts.transaction_account.accounts)?;
+
+    // CHECK enough DID authorities have signed or approved the transaction
+    // Transactions proposed by an unauthorized signer are authorized by a superuser middleware instead
+    if !allow_unauthorized_signer {
+        verify_threshold(
+            &all_accounts,
+            &resolve_controller_chain(&all_accounts, &controller_chain)?,
+            &ctx.accounts.did,
+            did_account_bump,
+            cryptid_account.threshold,
+            &ctx.accounts.transaction_account.approvers,
+        )?;
     }
 
-    // CHECK All middleware have approved the transaction (specifically the last one)
+    // CHECK All middleware in the cryptid account's pipeline have approved the transaction
     // TODO(ticket): Verification done in instruction body. Move back with Anchor generator
-    require!(
-        ctx.accounts.transaction_account.approved_middleware == cryptid_account.middleware,
-        CryptidError::IncorrectMiddleware
-    );
+    ctx.accounts
+        .transaction_account
+        .check_middlewares_approved(&cryptid_account.middlewares)?;
+
+    // CHECK sequenced transactions are executed in the order they were proposed
+    cryptid_account.check_transaction_sequence(ctx.accounts.transaction_account.sequence)?;
+
+    // The transaction account is closed after execution
+    cryptid_account.close_transaction(ctx.accounts.transaction_account.sequence, false)?;
+    cryptid_account.save(&ctx.accounts.cryptid_account)?;
 
     if debug {
         msg!(
@@ -177,18 +190,30 @@ pub fn execute_transaction<'info>(
     // At this point, we are safe that the signer is a valid owner of the cryptid account. We can execute the instructions
     CPI::execute_instructions(
         &ctx.accounts.transaction_account.instructions,
-        &ctx.all_accounts(),
+        0,
+        proposed_accounts,
         &ctx.accounts.did_program.key(),
         &ctx.accounts.did.key(),
         &cryptid_account,
         &ctx.accounts.cryptid_account.to_account_info(),
         cryptid_account_bump,
-        debug,
+        flags,
     )?;
 
     // MArk the tx as executed to prevent double-spends
     ctx.accounts.transaction_account.state = TransactionState::Executed;
 
+    emit!(TransactionExecuted {
+        cryptid_account: ctx.accounts.cryptid_account.key(),
+        did: ctx.accounts.did.key(),
+        transaction_account: Some(ctx.accounts.transaction_account.key()),
+        authority: ctx.accounts.authority.key(),
+        program_ids: TransactionExecuted::program_ids(
+            &ctx.accounts.transaction_account.instructions,
+            &ctx.accounts.transaction_account.accounts,
+        ),
+    });
+
     Ok(())
 }
 
@@ -0,0 +1,234 @@
+use crate::error::CryptidError;
+use crate::events::{TransactionExecuted, TransactionStateChanged};
+use crate::instructions::util::*;
+use crate::state::did_reference::DIDReference;
+use crate::state::transaction_account::TransactionAccount;
+use crate::state::transaction_flags::TransactionFlags;
+use crate::state::transaction_state::TransactionState;
+use crate::util::cpi::*;
+use crate::util::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(
+/// A vector of controller account indices and their associated DID authority keys (to allow for generative cases).
+controller_chain: Vec<DIDReference>,
+/// The bump seed for the Cryptid signer
+cryptid_account_bump: u8,
+/// Index of the cryptid account
+cryptid_account_index: u32,
+/// The bump seed for the Did Account
+did_account_bump: u8,
+/// Additional flags
+flags: u8,
+/// The maximum number of instructions to execute
+count: u8,
+)]
+pub struct ExecuteTransactionChunk<'info> {
+    /// The Cryptid instance to execute with
+    /// CHECK: Cryptid Account can be generative and non-generative
+    #[account(mut)]
+    pub cryptid_account: UncheckedAccount<'info>,
+    /// The DID on the Cryptid instance
+    /// CHECK: DID Account can be generative or not
+    pub did: UncheckedAccount<'info>,
+    /// The program for the DID
+    pub did_program: Program<'info, SolDID>,
+    /// The signer of the transaction
+    pub authority: Signer<'info>,
+    /// CHECK: Rent destination account is checked against the transaction account.
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+    /// The transaction to execute.
+    /// Closed once its last instruction has executed
+    #[account(
+    mut,
+    has_one = cryptid_account @ CryptidError::WrongCryptidAccount,
+    // the rent is refunded to the payer, unless they opted out when proposing the transaction
+    constraint = transaction_account.is_valid_rent_destination(destination.key) @ CryptidError::InvalidRentDestination,
+    // only transactions that opted in to non-atomic execution when proposed can be executed in chunks
+    constraint = transaction_account.flags().contains(TransactionFlags::NON_ATOMIC) @ CryptidError::AtomicTransaction,
+    // only "Ready" transactions, or ones that are part-way through execution, can be executed
+    constraint = matches!(transaction_account.state, TransactionState::Ready | TransactionState::Executing { .. }) @ CryptidError::InvalidTransactionState,
+    // only authorized transactions, ones that were proposed by a DID authority,
+    // or authorized by a superuser middleware, can be executed
+    constraint = transaction_account.authorized @ CryptidError::UnauthorizedTransaction,
+    )]
+    pub transaction_account: Account<'info, TransactionAccount>,
+}
+/// Collect all accounts as a single vector so that they can be referenced by index by instructions
+/// The order must be preserved between Propose and Execute
+impl<'a, 'b, 'c, 'info> AllAccounts<'a, 'b, 'c, 'info>
+    for Context<'a, 'b, 'c, 'info, ExecuteTransactionChunk<'info>>
+{
+    fn all_accounts(&self) -> Vec<&AccountInfo<'info>> {
+        [
+            self.accounts.cryptid_account.as_ref(),
+            self.accounts.did.as_ref(),
+            self.accounts.did_program.as_ref(),
+            self.accounts.authority.as_ref(),
+        ]
+        .into_iter()
+        .chain(self.remaining_accounts.iter())
+        .collect()
+    }
+
+    fn get_accounts_by_indexes(&self, indexes: &[u8]) -> Result<Vec<&AccountInfo<'info>>> {
+        let accounts = self.all_accounts();
+        resolve_by_index(indexes, &accounts)
+    }
+}
+
+/// Executes up to `count` instructions of a non-atomic transaction, resuming from the last executed instruction.
+///
+/// This allows transactions that exceed the compute budget of a single Solana transaction to be
+/// executed across several. Each chunk is atomic, but the transaction as a whole is not:
+/// if a chunk fails, the chunks before it are not rolled back, and the transaction stays in the
+/// Executing state, so the failing chunk can be retried, or the transaction closed.
+/// The transaction account is closed once its last instruction has executed.
+pub fn execute_transaction_chunk<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecuteTransactionChunk<'info>>,
+    controller_chain: Vec<DIDReference>,
+    cryptid_account_bump: u8,
+    cryptid_account_index: u32,
+    did_account_bump: u8,
+    flags: u8,
+    count: u8,
+) -> Result<()> {
+    let flags = ExecuteFlags::parse(flags)?;
+    let debug = flags.contains(ExecuteFlags::DEBUG);
+
+    require_gt!(count, 0, CryptidError::InvalidChunkSize);
+
+    // if there is an unauthorized signer, it must be the one executing the transaction
+    // in this case, a superuser middleware must have authorized the transaction
+    if let Some(unauthorized_signer) = ctx.accounts.transaction_account.unauthorized_signer {
+        require_keys_eq!(
+            ctx.accounts.authority.key(),
+            unauthorized_signer,
+            CryptidError::KeyMustBeSigner
+        );
+    }
+
+    let all_accounts = ctx.all_accounts();
+
+    let allow_unauthorized_signer = ctx
+        .accounts
+        .transaction_account
+        .unauthorized_signer
+        .is_some();
+
+    let mut cryptid_account = get_cryptid_account_checked(
+        &all_accounts,
+        &controller_chain,
+        &ctx.accounts.cryptid_account,
+        &ctx.accounts.did,
+        &ctx.accounts.did_program,
+        &ctx.accounts.authority,
+        did_account_bump,
+        cryptid_account_index,
+        cryptid_account_bump,
+        allow_unauthorized_signer,
+    )?;
+
+    // CHECK the transaction has not expired since it was proposed.
+    // Once execution has started, it may be completed even after the expiry,
+    // so that the transaction is not left part-executed.
+    let start = match ctx.accounts.transaction_account.state {
+        TransactionState::Executing { next_index } => next_index as usize,
+        _ => {
+            ctx.accounts.transaction_account.check_not_expired()?;
+            0
+        }
+    };
+
+    // CHECK the accounts have not been switched, added or removed since the transaction was proposed
+    // Additional DID authorities may follow them as signers, but are not passed to the instructions
+    let proposed_accounts =
+        check_transaction_accounts(&all_accounts, &ctx.accounts.transaction_account.accounts)?;
+
+    // CHECK enough DID authorities have signed or approved the transaction
+    if !allow_unauthorized_signer {
+        verify_threshold(
+            &all_accounts,
+            &resolve_controller_chain(&all_accounts, &controller_chain)?,
+            &ctx.accounts.did,
+            did_account_bump,
+            cryptid_account.threshold,
+            &ctx.accounts.transaction_account.approvers,
+        )?;
+    }
+
+    // CHECK All middleware in the cryptid account's pipeline have approved the transaction
+    ctx.accounts
+        .transaction_account
+        .check_middlewares_approved(&cryptid_account.middlewares)?;
+
+    // CHECK sequenced transactions are executed in the order they were proposed
+    cryptid_account.check_transaction_sequence(ctx.accounts.transaction_account.sequence)?;
+
+    let instruction_count = ctx.accounts.transaction_account.instructions.len();
+    let end = instruction_count.min(start + count as usize);
+    let is_complete = end == instruction_count;
+
+    if is_complete {
+        // The transaction account is closed after the last chunk is executed
+        cryptid_account.close_transaction(ctx.accounts.transaction_account.sequence, false)?;
+        cryptid_account.save(&ctx.accounts.cryptid_account)?;
+    }
+
+    if debug {
+        msg!(
+            "Executing instructions {} to {} of {}",
+            start,
+            end,
+            instruction_count
+        );
+    }
+
+    CPI::execute_instructions(
+        &ctx.accounts.transaction_account.instructions[start..end],
+        start,
+        proposed_accounts,
+        &ctx.accounts.did_program.key(),
+        &ctx.accounts.did.key(),
+        &cryptid_account,
+        &ctx.accounts.cryptid_account.to_account_info(),
+        cryptid_account_bump,
+        flags,
+    )?;
+
+    if is_complete {
+        // Mark the tx as executed to prevent double-spends
+        ctx.accounts.transaction_account.state = TransactionState::Executed;
+        ctx.accounts
+            .transaction_account
+            .close(ctx.accounts.destination.to_account_info())?;
+
+        emit!(TransactionExecuted {
+            cryptid_account: ctx.accounts.cryptid_account.key(),
+            did: ctx.accounts.did.key(),
+            transaction_account: Some(ctx.accounts.transaction_account.key()),
+            authority: ctx.accounts.authority.key(),
+            program_ids: TransactionExecuted::program_ids(
+                &ctx.accounts.transaction_account.instructions,
+                &ctx.accounts.transaction_account.accounts,
+            ),
+        });
+    } else {
+        let previous_state = ctx.accounts.transaction_account.state.clone();
+        let state = TransactionState::executing(end)?;
+        ctx.accounts.transaction_account.state = state.clone();
+
+        emit!(TransactionStateChanged {
+            cryptid_account: ctx.accounts.cryptid_account.key(),
+            did: ctx.accounts.did.key(),
+            transaction_account: ctx.accounts.transaction_account.key(),
+            authority: ctx.accounts.authority.key(),
+            previous_state,
+            state,
+        });
+    }
+
+    Ok(())
+}
@@ -1,14 +1,17 @@
 use crate::error::CryptidError;
-use crate::instructions::util::{get_cryptid_account_checked, resolve_by_index, AllAccounts};
-use crate::state::abbreviated_account_meta::AbbreviatedAccountMeta;
+use crate::events::{TransactionExtended, TransactionStateChanged};
+use crate::instructions::util::{
+    check_instruction_privileges, check_not_reentrant, get_cryptid_account_checked,
+    resolve_by_index, AllAccounts,
+};
 use crate::state::abbreviated_instruction_data::AbbreviatedInstructionData;
+use crate::state::cryptid_account::CryptidAccount;
 use crate::state::did_reference::DIDReference;
 use crate::state::instruction_size::InstructionSize;
 use crate::state::transaction_account::TransactionAccount;
 use crate::state::transaction_state::TransactionState;
 use crate::util::SolDID;
 use anchor_lang::prelude::*;
-use itertools::Itertools;
 
 #[derive(Accounts)]
 #[instruction(
@@ -50,11 +53,11 @@ pub struct ExtendTransaction<'info> {
         // only transactions in "not ready" state can be extended
         constraint = transaction_account.state == TransactionState::NotReady @ CryptidError::InvalidTransactionState,
         // resize the transaction account to fit the new instructions
-        realloc = TransactionAccount::calculate_size(
-                transaction_account.accounts.len() + num_accounts as usize,
-                InstructionSize::from_iter_to_iter(
-                    instructions.iter().chain(transaction_account.instructions.iter())
-                )
+        realloc = ExtendTransaction::extended_size(
+                &transaction_account,
+                &instructions,
+                num_accounts,
+                CryptidAccount::max_middleware_approvals_of(&cryptid_account)?
             ),
         realloc::payer = authority,
         realloc::zero = false,
@@ -86,43 +89,65 @@ impl<'a, 'b, 'c, 'info> AllAccounts<'a, 'b, 'c, 'info>
 }
 
 impl ExtendTransaction<'_> {
+    /// The size of `transaction_account` once extended with `new_instructions`, which refer to
+    /// up to `num_accounts` accounts not already in the transaction (besides the named accounts).
+    fn extended_size(
+        transaction_account: &TransactionAccount,
+        new_instructions: &[AbbreviatedInstructionData],
+        num_accounts: u8,
+        num_middleware_approvals: usize,
+    ) -> usize {
+        TransactionAccount::calculate_size(
+            // calculate_size counts the named accounts, which the transaction accounts include
+            transaction_account.accounts.len().saturating_sub(4) + num_accounts as usize,
+            InstructionSize::from_iter_to_iter(
+                transaction_account
+                    .instructions
+                    .iter()
+                    .chain(new_instructions.iter()),
+            ),
+            // approvals are invalidated by the extension
+            0,
+            num_middleware_approvals,
+        )
+    }
+
+    /// Rewrite the account indices of `new_instructions`, which refer to `new_instruction_accounts`
+    /// (the accounts passed to this instruction), so that they refer to the transaction accounts instead.
+    /// Accounts already in `existing_accounts` keep their index, and each new account is given
+    /// the next free index, in order of first reference.
+    /// Returns the new accounts, to be appended to the transaction accounts.
     fn update_instructions(
-        transaction_account: &Account<TransactionAccount>,
-        new_instructions: &mut [&mut AbbreviatedInstructionData],
-        new_instruction_accounts: &[&AccountInfo],
-    ) -> Vec<Pubkey> {
-        let mut new_accounts_to_push = vec![];
-
-        // for each instruction, look at its accounts
-        // if the account is in transaction_account.accounts, update its index in the instruction
-        // if the account is not in transaction_account.accounts, add it to the transaction_accounts and update the index in the instruction
-        new_instructions.iter_mut().for_each(
-            |new_instruction: &mut &mut AbbreviatedInstructionData| {
-                new_instruction.accounts.iter_mut().for_each(
-                    |mut new_instruction_account_meta: &mut AbbreviatedAccountMeta| {
-                        let account =
-                            new_instruction_accounts[new_instruction_account_meta.key as usize];
-                        if let Some((index, _key)) = transaction_account
-                            .accounts
-                            .iter()
-                            .find_position(|existing_account| *existing_account == account.key)
-                        {
-                            // The account from the new instruction is already in the transaction account
-                            // with index `index`
-                            new_instruction_account_meta.key = index as u8;
-                        } else {
-                            // The account from the new instruction is a new account
-                            // add it to the array of accounts, and set the index to the last index
-                            new_instruction_account_meta.key =
-                                transaction_account.accounts.len() as u8;
-                            new_accounts_to_push.push(*account.key);
-                        }
-                    },
-                );
-            },
-        );
+        existing_accounts: &[Pubkey],
+        new_instructions: &mut [AbbreviatedInstructionData],
+        new_instruction_accounts: &[Pubkey],
+    ) -> Result<Vec<Pubkey>> {
+        let mut new_accounts_to_push: Vec<Pubkey> = vec![];
+
+        let mut merge_account = |index: u8| -> Result<u8> {
+            let account = new_instruction_accounts
+                .get(index as usize)
+                .ok_or_else(|| error!(CryptidError::IndexOutOfRange))?;
+            let merged_index = existing_accounts
+                .iter()
+                .chain(new_accounts_to_push.iter())
+                .position(|existing_account| existing_account == account)
+                .unwrap_or_else(|| {
+                    // The account is not yet in the transaction account. Add it to the end.
+                    new_accounts_to_push.push(*account);
+                    existing_accounts.len() + new_accounts_to_push.len() - 1
+                });
+            u8::try_from(merged_index).map_err(|_| error!(CryptidError::IndexOutOfRange))
+        };
+
+        for new_instruction in new_instructions.iter_mut() {
+            new_instruction.program_id = merge_account(new_instruction.program_id)?;
+            for new_instruction_account_meta in new_instruction.accounts.iter_mut() {
+                new_instruction_account_meta.key = merge_account(new_instruction_account_meta.key)?;
+            }
+        }
 
-        new_accounts_to_push
+        Ok(new_accounts_to_push)
     }
 }
 
@@ -166,32 +191,219 @@ pub fn extend_transaction<'info>(
         allow_unauthorized_signer,
     )?;
 
-    let mut mutable_references: Vec<&mut AbbreviatedInstructionData> =
-        instructions.iter_mut().collect();
+    // Make sure that all new instructions reference accounts in bound, with valid account metas.
+    for instruction in instructions.iter() {
+        instruction.validate(all_accounts.len())?;
+    }
+    check_instruction_privileges(&instructions, &all_accounts)?;
 
+    let new_instruction_accounts: Vec<Pubkey> = all_accounts.iter().map(|a| *a.key).collect();
+    check_not_reentrant(&instructions, &new_instruction_accounts)?;
     let new_accounts_to_push = ExtendTransaction::update_instructions(
-        &ctx.accounts.transaction_account,
-        &mut mutable_references,
-        &all_accounts,
-    );
+        &ctx.accounts.transaction_account.accounts,
+        &mut instructions,
+        &new_instruction_accounts,
+    )?;
 
     // we have updated all the instruction indices. Add them to the transaction account
     ctx.accounts
         .transaction_account
         .accounts
         .extend(new_accounts_to_push);
+    let instruction_count =
+        u16::try_from(instructions.len()).map_err(|_| error!(CryptidError::TooManyInstructions))?;
     ctx.accounts
         .transaction_account
         .instructions
         .extend(instructions);
+    ctx.accounts.transaction_account.check_instruction_count()?;
+
+    // Approvals were given for the transaction before it was extended, so they no longer apply
+    ctx.accounts.transaction_account.approvers.clear();
 
     // Update the state of the transaction account (setting it to Ready as needed)
-    require_neq!(
-        state,
-        TransactionState::Executed,
-        CryptidError::InvalidTransactionState
-    );
-    ctx.accounts.transaction_account.state = state;
+    require!(state.is_pending(), CryptidError::InvalidTransactionState);
+    let previous_state = ctx.accounts.transaction_account.state.clone();
+    ctx.accounts.transaction_account.state = state.clone();
+
+    emit!(TransactionExtended {
+        cryptid_account: ctx.accounts.cryptid_account.key(),
+        did: ctx.accounts.did.key(),
+        transaction_account: ctx.accounts.transaction_account.key(),
+        authority: ctx.accounts.authority.key(),
+        instruction_count,
+    });
+    if previous_state != state {
+        emit!(TransactionStateChanged {
+            cryptid_account: ctx.accounts.cryptid_account.key(),
+            did: ctx.accounts.did.key(),
+            transaction_account: ctx.accounts.transaction_account.key(),
+            authority: ctx.accounts.authority.key(),
+            previous_state,
+            state,
+        });
+    }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::state::abbreviated_account_meta::AbbreviatedAccountMeta;
+    use crate::state::transaction_account::DISCRIMINATOR_SIZE;
+    use crate::state::transaction_expiry::TransactionExpiry;
+    use anchor_lang::prelude::borsh::BorshSerialize;
+
+    fn instruction(program_id: u8, keys: &[u8]) -> AbbreviatedInstructionData {
+        AbbreviatedInstructionData {
+            program_id,
+            accounts: keys
+                .iter()
+                .map(|key| AbbreviatedAccountMeta { key: *key, meta: 0 })
+                .collect(),
+            data: vec![],
+        }
+    }
+
+    fn keys(instruction: &AbbreviatedInstructionData) -> Vec<u8> {
+        instruction.accounts.iter().map(|meta| meta.key).collect()
+    }
+
+    /// A transaction account with the largest value of each optional field, as allocated on-chain
+    fn transaction_account(
+        accounts: Vec<Pubkey>,
+        instructions: Vec<AbbreviatedInstructionData>,
+    ) -> TransactionAccount {
+        TransactionAccount {
+            cryptid_account: Default::default(),
+            did: Default::default(),
+            accounts,
+            instructions,
+            approved_middlewares: vec![],
+            slot: 0,
+            expiry: Some(TransactionExpiry::Slot(0)),
+            state: TransactionState::Executing { next_index: 0 },
+            unauthorized_signer: Some(Default::default()),
+            authorized: true,
+            approvers: vec![],
+            payer: Default::default(),
+            flags: 0,
+            sequence: Some(0),
+        }
+    }
+
+    /// Extend `transaction_account` as the instruction does, returning its size before and after
+    fn extend(
+        transaction_account: &mut TransactionAccount,
+        mut instructions: Vec<AbbreviatedInstructionData>,
+        new_instruction_accounts: &[Pubkey],
+    ) -> (usize, usize) {
+        let num_accounts = u8::try_from(new_instruction_accounts.len() - 4).unwrap();
+        let allocated_size =
+            ExtendTransaction::extended_size(transaction_account, &instructions, num_accounts, 0);
+
+        let new_accounts = ExtendTransaction::update_instructions(
+            &transaction_account.accounts,
+            &mut instructions,
+            new_instruction_accounts,
+        )
+        .unwrap();
+        transaction_account.accounts.extend(new_accounts);
+        transaction_account.instructions.extend(instructions);
+
+        let size = DISCRIMINATOR_SIZE
+            + BorshSerialize::try_to_vec(transaction_account)
+                .unwrap()
+                .len();
+        (allocated_size, size)
+    }
+
+    #[test]
+    fn update_instructions_fits_the_extended_size() {
+        let existing_accounts: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+        let mut account =
+            transaction_account(existing_accounts.clone(), vec![instruction(4, &[0])]);
+
+        // the named accounts and two new accounts
+        let mut new_instruction_accounts = existing_accounts[..4].to_vec();
+        new_instruction_accounts.extend([Pubkey::new_unique(), Pubkey::new_unique()]);
+        let (allocated_size, size) = extend(
+            &mut account,
+            vec![instruction(5, &[0, 4])],
+            &new_instruction_accounts,
+        );
+        assert_eq!(allocated_size, size);
+
+        // accounts already in the transaction are not added again, so need less space than allocated
+        let mut new_instruction_accounts = existing_accounts[..4].to_vec();
+        new_instruction_accounts.extend([existing_accounts[4], Pubkey::new_unique()]);
+        let (allocated_size, size) = extend(
+            &mut account,
+            vec![instruction(5, &[4])],
+            &new_instruction_accounts,
+        );
+        assert_eq!(allocated_size, size + 32);
+    }
+
+    #[test]
+    fn update_instructions_assigns_sequential_indices() {
+        let existing_accounts: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+        let (new_a, new_b, program) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        // the accounts passed to extend: the named accounts, then the new accounts
+        let mut new_instruction_accounts = existing_accounts[..4].to_vec();
+        new_instruction_accounts.extend([new_a, existing_accounts[4], new_b, program]);
+
+        let mut instructions = vec![instruction(7, &[0, 4, 6]), instruction(7, &[5, 6, 4])];
+        let new_accounts = ExtendTransaction::update_instructions(
+            &existing_accounts,
+            &mut instructions,
+            &new_instruction_accounts,
+        )
+        .unwrap();
+
+        // each new account is added once, in order of first reference
+        assert_eq!(new_accounts, vec![program, new_a, new_b]);
+        assert_eq!(instructions[0].program_id, 5);
+        assert_eq!(keys(&instructions[0]), vec![0, 6, 7]);
+        assert_eq!(instructions[1].program_id, 5);
+        assert_eq!(keys(&instructions[1]), vec![4, 7, 6]);
+    }
+
+    #[test]
+    fn update_instructions_reuses_existing_accounts() {
+        let existing_accounts: Vec<Pubkey> = (0..6).map(|_| Pubkey::new_unique()).collect();
+        // the same accounts passed in a different order
+        let new_instruction_accounts: Vec<Pubkey> =
+            existing_accounts.iter().rev().cloned().collect();
+
+        let mut instructions = vec![instruction(0, &[1, 2])];
+        let new_accounts = ExtendTransaction::update_instructions(
+            &existing_accounts,
+            &mut instructions,
+            &new_instruction_accounts,
+        )
+        .unwrap();
+
+        assert!(new_accounts.is_empty());
+        assert_eq!(instructions[0].program_id, 5);
+        assert_eq!(keys(&instructions[0]), vec![4, 3]);
+    }
+
+    #[test]
+    fn update_instructions_rejects_out_of_range_indices() {
+        let existing_accounts = vec![Pubkey::new_unique()];
+        let mut instructions = vec![instruction(0, &[1])];
+
+        assert!(ExtendTransaction::update_instructions(
+            &existing_accounts,
+            &mut instructions,
+            &existing_accounts,
+        )
+        .is_err());
+    }
+}
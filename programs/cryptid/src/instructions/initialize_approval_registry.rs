@@ -0,0 +1,49 @@
+use crate::error::CryptidError;
+use crate::program::Cryptid;
+use crate::state::approval_registry::ApprovalRegistry;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(
+/// The middleware programs to register
+middleware_programs: Vec<Pubkey>,
+/// The authority that can update the registry, e.g. a multisig that remains once the program is made immutable
+registry_authority: Pubkey,
+)]
+pub struct InitializeApprovalRegistry<'info> {
+    #[account(
+    init,
+    payer = authority,
+    space = 8 + ApprovalRegistry::calculate_size(middleware_programs.len()),
+    seeds = [ApprovalRegistry::SEED_PREFIX],
+    bump
+    )]
+    pub approval_registry: Account<'info, ApprovalRegistry>,
+    /// The upgrade authority of the cryptid program
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+    constraint = cryptid_program.programdata_address()? == Some(program_data.key()) @ CryptidError::NotUpgradeAuthority,
+    )]
+    pub cryptid_program: Program<'info, Cryptid>,
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Initialize the global registry of middleware programs that can approve transactions
+pub fn initialize_approval_registry(
+    ctx: Context<InitializeApprovalRegistry>,
+    middleware_programs: Vec<Pubkey>,
+    registry_authority: Pubkey,
+) -> Result<()> {
+    ApprovalRegistry::check_initializer(
+        ctx.accounts.program_data.upgrade_authority_address,
+        ctx.accounts.authority.key,
+    )?;
+
+    let approval_registry = &mut ctx.accounts.approval_registry;
+    approval_registry.authority = registry_authority;
+    approval_registry.middleware_programs =
+        approval_registry.updated_middleware_programs(&middleware_programs, &[]);
+    approval_registry.validate()
+}
@@ -0,0 +1,51 @@
+use crate::id;
+use crate::state::cryptid_account::{CryptidAccount, LegacyCryptidAccount};
+use crate::state::transaction_account::DISCRIMINATOR_SIZE;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+#[derive(Accounts)]
+pub struct MigrateCryptidAccount<'info> {
+    /// The cryptid account to migrate, in the legacy layout
+    /// CHECK: Deserialized as a legacy cryptid account in the instruction body.
+    #[account(mut, owner = id())]
+    pub cryptid_account: UncheckedAccount<'info>,
+    /// Pays for any additional rent required by the migrated account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Upgrade a cryptid account created by an earlier version of the program to the current layout.
+/// The middleware, index and superuser middlewares of the account are preserved, and all other fields
+/// take the values of a newly created cryptid account, so any signer can migrate any cryptid account.
+pub fn migrate_cryptid_account(ctx: Context<MigrateCryptidAccount>) -> Result<()> {
+    let cryptid_account_info = ctx.accounts.cryptid_account.to_account_info();
+    let legacy_account =
+        LegacyCryptidAccount::try_deserialize(&cryptid_account_info.try_borrow_data()?)?;
+    let cryptid_account = CryptidAccount::from(legacy_account);
+
+    let space = DISCRIMINATOR_SIZE
+        + CryptidAccount::calculate_size(
+            cryptid_account.middlewares.len(),
+            cryptid_account.superuser_middleware.len(),
+        );
+    let shortfall = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(cryptid_account_info.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: cryptid_account_info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    cryptid_account_info.realloc(space, false)?;
+
+    cryptid_account.save(&cryptid_account_info)
+}
@@ -2,21 +2,39 @@
 
 // pub mod cancel_transaction;
 pub mod approve_execution;
+pub mod approve_transaction;
+pub mod close_cryptid_account;
 pub mod close_transaction;
 pub mod create_cryptid_account;
 pub mod direct_execute;
 pub mod execute_transaction;
+pub mod execute_transaction_chunk;
 pub mod extend_transaction;
+pub mod initialize_approval_registry;
+pub mod migrate_cryptid_account;
+pub mod propose_sequenced_transaction;
 pub mod propose_transaction;
+pub mod reclaim_transaction;
 pub mod superuser_approve_execution;
+pub mod update_approval_registry;
+pub mod update_cryptid_account;
 
 pub mod util;
 
 pub use approve_execution::*;
+pub use approve_transaction::*;
+pub use close_cryptid_account::*;
 pub use close_transaction::*;
 pub use create_cryptid_account::*;
 pub use direct_execute::*;
 pub use execute_transaction::*;
+pub use execute_transaction_chunk::*;
 pub use extend_transaction::*;
+pub use initialize_approval_registry::*;
+pub use migrate_cryptid_account::*;
+pub use propose_sequenced_transaction::*;
 pub use propose_transaction::*;
+pub use reclaim_transaction::*;
 pub use superuser_approve_execution::*;
+pub use update_approval_registry::*;
+pub use update_cryptid_account::*;
@@ -0,0 +1,173 @@
+use crate::error::CryptidError;
+use crate::events::TransactionProposed;
+use crate::id;
+use crate::instructions::propose_transaction::initialize_transaction_account;
+use crate::instructions::util::{
+    check_instruction_privileges, get_cryptid_account_checked, resolve_by_index, AllAccounts,
+};
+use crate::state::abbreviated_instruction_data::AbbreviatedInstructionData;
+use crate::state::cryptid_account::CryptidAccount;
+use crate::state::did_reference::DIDReference;
+use crate::state::instruction_size::InstructionSize;
+use crate::state::transaction_account::TransactionAccount;
+use crate::state::transaction_expiry::TransactionExpiry;
+use crate::state::transaction_state::TransactionState;
+use crate::util::SolDID;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(
+/// A vector of controller account indices and their associated DID authority keys (to allow for generative cases).
+controller_chain: Vec<DIDReference>,
+/// The bump seed for the Cryptid signer
+cryptid_account_bump: u8,
+/// Index of the cryptid account
+cryptid_account_index: u32,
+/// The bump seed for the Did Account
+did_account_bump: u8,
+/// The sequence number of the transaction. Must be the next sequence number of the cryptid account
+sequence: u64,
+/// The state in which to create the transaction
+state: TransactionState,
+/// Must be false. A non-authority cannot propose a sequenced transaction, as it would hold up the transactions after it
+allow_unauthorized: bool,
+/// The point after which the transaction can no longer be approved or executed, if any
+expiry: Option<TransactionExpiry>,
+/// Additional flags, see [`TransactionFlags`]
+flags: u8,
+/// The instructions to execute
+instructions: Vec<AbbreviatedInstructionData>,
+num_accounts: u8,
+)]
+pub struct ProposeSequencedTransaction<'info> {
+    /// The Cryptid instance that can execute the transaction.
+    /// CHECK: Verification done in instruction body. Must be non-generative to hold the sequence.
+    #[account(mut)]
+    pub cryptid_account: UncheckedAccount<'info>,
+    /// The did account owner of the Cryptid instance
+    /// CHECK: Unchecked to allow generative DID accounts.
+    #[account()]
+    pub did: UncheckedAccount<'info>,
+    /// The program for the DID
+    pub did_program: Program<'info, SolDID>,
+    #[account(mut)]
+    authority: Signer<'info>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [TransactionAccount::SEED_PREFIX, cryptid_account.key().as_ref(), sequence.to_le_bytes().as_ref()],
+        bump,
+        space = TransactionAccount::calculate_size(
+            num_accounts.into(),
+            InstructionSize::from_iter_to_iter(
+                instructions.iter()
+            ),
+            0,
+            // reserve space for the approvals of the cryptid account's middlewares
+            CryptidAccount::max_middleware_approvals_of(&cryptid_account)?
+       ))
+    ]
+    transaction_account: Account<'info, TransactionAccount>,
+    system_program: Program<'info, System>,
+}
+
+/// Collect all accounts as a single vector so that they can be referenced by index by instructions
+impl<'a, 'b, 'c, 'info> AllAccounts<'a, 'b, 'c, 'info>
+    for Context<'a, 'b, 'c, 'info, ProposeSequencedTransaction<'info>>
+{
+    fn all_accounts(&self) -> Vec<&AccountInfo<'info>> {
+        [
+            self.accounts.cryptid_account.as_ref(),
+            self.accounts.did.as_ref(),
+            self.accounts.did_program.as_ref(),
+            self.accounts.authority.as_ref(),
+        ]
+        .into_iter()
+        .chain(self.remaining_accounts.iter())
+        .collect()
+    }
+
+    fn get_accounts_by_indexes(&self, indexes: &[u8]) -> Result<Vec<&AccountInfo<'info>>> {
+        let accounts = self.all_accounts();
+        resolve_by_index(indexes, &accounts)
+    }
+}
+
+/// Propose a transaction at a PDA derived from the cryptid account and its next sequence number,
+/// so that the pending transactions of a cryptid account can be enumerated.
+/// Sequenced transactions must be executed or closed in the order in which they were proposed,
+/// though an expired transaction can be closed ahead of its turn, skipping those before it.
+pub fn propose_sequenced_transaction<'info>(
+    ctx: Context<'_, '_, '_, 'info, ProposeSequencedTransaction<'info>>,
+    controller_chain: Vec<DIDReference>,
+    cryptid_account_bump: u8,
+    cryptid_account_index: u32,
+    did_account_bump: u8,
+    sequence: u64,
+    state: TransactionState,
+    allow_unauthorized: bool,
+    expiry: Option<TransactionExpiry>,
+    flags: u8,
+    instructions: Vec<AbbreviatedInstructionData>,
+) -> Result<()> {
+    require!(
+        !allow_unauthorized,
+        CryptidError::UnauthorizedSequencedTransaction
+    );
+
+    let all_accounts = ctx.all_accounts();
+
+    let mut cryptid_account = get_cryptid_account_checked(
+        &all_accounts,
+        &controller_chain,
+        &ctx.accounts.cryptid_account,
+        &ctx.accounts.did,
+        &ctx.accounts.did_program,
+        &ctx.accounts.authority,
+        did_account_bump,
+        cryptid_account_index,
+        cryptid_account_bump,
+        allow_unauthorized,
+    )?;
+
+    // Generative cryptid accounts have no data in which to store the sequence
+    require!(
+        ctx.accounts.cryptid_account.owner == &id(),
+        CryptidError::SequenceOnGenerativeAccount
+    );
+    require_eq!(
+        sequence,
+        cryptid_account.next_transaction_sequence,
+        CryptidError::InvalidTransactionSequence
+    );
+
+    // CHECK the instructions do not require privileges that can never be granted at execution
+    check_instruction_privileges(&instructions, &all_accounts)?;
+
+    let account_keys = all_accounts.iter().map(|a| *a.key).collect();
+    initialize_transaction_account(
+        &mut ctx.accounts.transaction_account,
+        &mut cryptid_account,
+        account_keys,
+        state,
+        allow_unauthorized,
+        expiry,
+        flags,
+        instructions,
+    )?;
+    ctx.accounts.transaction_account.sequence = Some(sequence);
+
+    emit!(TransactionProposed {
+        cryptid_account: ctx.accounts.cryptid_account.key(),
+        did: ctx.accounts.did.key(),
+        transaction_account: ctx.accounts.transaction_account.key(),
+        authority: ctx.accounts.authority.key(),
+        state: ctx.accounts.transaction_account.state.clone(),
+        sequence: Some(sequence),
+    });
+
+    cryptid_account.next_transaction_sequence = sequence
+        .checked_add(1)
+        .ok_or(CryptidError::InvalidTransactionSequence)?;
+    cryptid_account.save(&ctx.accounts.cryptid_account)
+}
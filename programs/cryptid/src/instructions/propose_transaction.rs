@@ -1,9 +1,16 @@
 use crate::error::CryptidError;
-use crate::instructions::util::{get_cryptid_account_checked, resolve_by_index, AllAccounts};
+use crate::events::TransactionProposed;
+use crate::instructions::util::{
+    check_instruction_privileges, check_not_reentrant, get_cryptid_account_checked,
+    resolve_by_index, AllAccounts, AUTHORITY_ACCOUNT_INDEX,
+};
 use crate::state::abbreviated_instruction_data::AbbreviatedInstructionData;
+use crate::state::cryptid_account::CryptidAccount;
 use crate::state::did_reference::DIDReference;
 use crate::state::instruction_size::InstructionSize;
 use crate::state::transaction_account::TransactionAccount;
+use crate::state::transaction_expiry::TransactionExpiry;
+use crate::state::transaction_flags::TransactionFlags;
 use crate::state::transaction_state::TransactionState;
 use crate::util::SolDID;
 use anchor_lang::prelude::*;
@@ -22,6 +29,10 @@ did_account_bump: u8,
 state: TransactionState,
 /// True if the transaction account is being proposed by a non-authority on the DID
 allow_unauthorized: bool,
+/// The point after which the transaction can no longer be approved or executed, if any
+expiry: Option<TransactionExpiry>,
+/// Additional flags, see [`TransactionFlags`]
+flags: u8,
 /// The instructions to execute
 instructions: Vec<AbbreviatedInstructionData>,
 num_accounts: u8,
@@ -30,6 +41,7 @@ pub struct ProposeTransaction<'info> {
     /// The Cryptid instance that can execute the transaction.
     /// CHECK: Cryptid Account can be generative and non-generative
     #[account(
+        mut,
         // TODO(ticket): Verification done in instruction body. Move back with Anchor generator
         // seeds = [CryptidAccount::SEED_PREFIX, did_program.key().as_ref(), did.key().as_ref(), cryptid_account_index.to_le_bytes().as_ref()],
         // bump = cryptid_account_bump
@@ -50,7 +62,10 @@ pub struct ProposeTransaction<'info> {
             num_accounts.into(),
             InstructionSize::from_iter_to_iter(
                 instructions.iter()
-            )
+            ),
+            0,
+            // reserve space for the approvals of the cryptid account's middlewares
+            CryptidAccount::max_middleware_approvals_of(&cryptid_account)?
        ))
     ]
     transaction_account: Account<'info, TransactionAccount>,
@@ -89,11 +104,13 @@ pub fn propose_transaction<'info>(
     did_account_bump: u8,
     state: TransactionState,
     allow_unauthorized: bool,
+    expiry: Option<TransactionExpiry>,
+    flags: u8,
     instructions: Vec<AbbreviatedInstructionData>,
 ) -> Result<()> {
     let all_accounts = ctx.all_accounts();
 
-    let cryptid_account = get_cryptid_account_checked(
+    let mut cryptid_account = get_cryptid_account_checked(
         &all_accounts,
         &controller_chain,
         &ctx.accounts.cryptid_account,
@@ -105,6 +122,47 @@ pub fn propose_transaction<'info>(
         cryptid_account_bump,
         allow_unauthorized,
     )?;
+    // CHECK the instructions do not require privileges that can never be granted at execution
+    check_instruction_privileges(&instructions, &all_accounts)?;
+
+    let account_keys = all_accounts.iter().map(|a| *a.key).collect();
+    initialize_transaction_account(
+        &mut ctx.accounts.transaction_account,
+        &mut cryptid_account,
+        account_keys,
+        state,
+        allow_unauthorized,
+        expiry,
+        flags,
+        instructions,
+    )?;
+    ctx.accounts.transaction_account.sequence = None;
+
+    emit!(TransactionProposed {
+        cryptid_account: ctx.accounts.cryptid_account.key(),
+        did: ctx.accounts.did.key(),
+        transaction_account: ctx.accounts.transaction_account.key(),
+        authority: ctx.accounts.authority.key(),
+        state: ctx.accounts.transaction_account.state.clone(),
+        sequence: None,
+    });
+
+    cryptid_account.save(&ctx.accounts.cryptid_account)
+}
+
+/// Populate a newly created transaction account and register it against the cryptid account.
+/// `account_keys` are the keys of all accounts passed to the instruction, in order (see below).
+/// Shared between the propose instructions, which differ only in how the transaction account is addressed.
+pub(crate) fn initialize_transaction_account(
+    transaction_account: &mut TransactionAccount,
+    cryptid_account: &mut CryptidAccount,
+    account_keys: Vec<Pubkey>,
+    state: TransactionState,
+    allow_unauthorized: bool,
+    expiry: Option<TransactionExpiry>,
+    flags: u8,
+    instructions: Vec<AbbreviatedInstructionData>,
+) -> Result<()> {
     // Accounts stored into the transaction account are referenced by
     // the abbreviated instruction data by index
     // The same accounts must be passed, in the correct order, to the ExecuteTransaction instruction
@@ -120,20 +178,27 @@ pub fn propose_transaction<'info>(
     // Account indexes must reflect this, so the first entry
     // in the remaining accounts is referred to in the abbreviated instruction data as index 4,
     // despite being index 0 in the remaining accounts.
-    ctx.accounts.transaction_account.accounts = all_accounts.iter().map(|a| *a.key).collect();
+    transaction_account.accounts = account_keys;
 
-    // TODO: Set slot OR move any Slot / Expiry constraints to middleware
-    // ctx.accounts.transaction_account.slot = Clock::get()?.slot;
-    ctx.accounts.transaction_account.did = *ctx.accounts.did.key;
-    ctx.accounts.transaction_account.instructions = instructions;
-    ctx.accounts.transaction_account.cryptid_account = *ctx.accounts.cryptid_account.key;
-    ctx.accounts.transaction_account.approved_middleware = None;
-    ctx.accounts.transaction_account.unauthorized_signer = if allow_unauthorized {
-        Some(*ctx.accounts.authority.key)
+    let clock = Clock::get()?;
+    if let Some(expiry) = expiry {
+        require!(!expiry.is_expired(&clock), CryptidError::TransactionExpired);
+    }
+    transaction_account.slot = clock.slot;
+    transaction_account.expiry = expiry;
+    transaction_account.did = transaction_account.accounts[1];
+    transaction_account.instructions = instructions;
+    transaction_account.cryptid_account = transaction_account.accounts[0];
+    transaction_account.approved_middlewares = vec![];
+    transaction_account.unauthorized_signer = if allow_unauthorized {
+        Some(transaction_account.accounts[AUTHORITY_ACCOUNT_INDEX])
     } else {
         None
     };
-    ctx.accounts.transaction_account.authorized = !allow_unauthorized;
+    transaction_account.authorized = !allow_unauthorized;
+    transaction_account.approvers = vec![];
+    transaction_account.payer = transaction_account.accounts[AUTHORITY_ACCOUNT_INDEX];
+    transaction_account.flags = TransactionFlags::from_bits_truncate(flags).bits();
 
     // if the transaction is being created by an unauthorized signer,
     // then the cryptid account must have superuser middleware registered
@@ -143,26 +208,21 @@ pub fn propose_transaction<'info>(
         CryptidError::InvalidTransactionState
     );
 
-    // Make sure that all instructions reference accounts in bound.
-    let max_account_reference = ctx
-        .accounts
-        .transaction_account
-        .instructions
-        .iter()
-        .fold(0, |acc, x| acc.max(x.get_max_account_index()));
-    require_gt!(
-        ctx.accounts.transaction_account.accounts.len(),
-        max_account_reference as usize,
-        CryptidError::IndexOutOfRange
-    );
+    // Make sure that all instructions reference accounts in bound, with valid account metas.
+    for instruction in transaction_account.instructions.iter() {
+        instruction.validate(transaction_account.accounts.len())?;
+    }
+    check_not_reentrant(
+        &transaction_account.instructions,
+        &transaction_account.accounts,
+    )?;
 
-    // we cannot initiate a transaction in executed state.
-    require_neq!(
-        state,
-        TransactionState::Executed,
-        CryptidError::InvalidTransactionState
-    );
-    ctx.accounts.transaction_account.state = state;
+    transaction_account.check_instruction_count()?;
+
+    // we cannot initiate a transaction in executed or executing state.
+    require!(state.is_pending(), CryptidError::InvalidTransactionState);
+    transaction_account.state = state;
 
+    cryptid_account.open_transaction();
     Ok(())
 }
@@ -0,0 +1,73 @@
+use crate::error::CryptidError;
+use crate::events::TransactionClosed;
+use crate::id;
+use crate::state::cryptid_account::CryptidAccount;
+use crate::state::transaction_account::TransactionAccount;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ReclaimTransaction<'info> {
+    /// The Cryptid instance the transaction was proposed for
+    /// CHECK: Cryptid Account can be generative and non-generative. Bound to the transaction account by has_one.
+    #[account(mut)]
+    pub cryptid_account: UncheckedAccount<'info>,
+    /// The account that paid the rent for the transaction account, which receives it back
+    /// CHECK: Bound to the transaction account by has_one.
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+    /// The transaction account to reclaim
+    #[account(
+        mut,
+        close = payer,
+        has_one = cryptid_account @ CryptidError::WrongCryptidAccount,
+        has_one = payer @ CryptidError::AccountMismatch,
+        // transactions part-way through a non-atomic execution can only be closed by the DID authorities
+        constraint = transaction_account.state.is_pending() @ CryptidError::InvalidTransactionState,
+    )]
+    pub transaction_account: Account<'info, TransactionAccount>,
+}
+
+/// Close a transaction account that has expired, or has been open for longer than the
+/// cryptid account's `transaction_reclaim_age`, refunding the rent to the payer.
+/// Anyone can call this instruction.
+pub fn reclaim_transaction<'info>(
+    ctx: Context<'_, '_, '_, 'info, ReclaimTransaction<'info>>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let cryptid_account_info = ctx.accounts.cryptid_account.as_ref();
+
+    // Generative cryptid accounts have no settings, so cannot define a reclaim age
+    let mut cryptid_account = if cryptid_account_info.owner == &id() {
+        let account: Account<CryptidAccount> = Account::try_from(cryptid_account_info)?;
+        Some(account.into_inner())
+    } else {
+        None
+    };
+
+    let transaction_account = &ctx.accounts.transaction_account;
+    let transaction_reclaim_age = cryptid_account
+        .as_ref()
+        .and_then(|account| account.transaction_reclaim_age);
+    require!(
+        transaction_account.is_reclaimable(&clock, transaction_reclaim_age),
+        CryptidError::TransactionNotReclaimable
+    );
+
+    if let Some(cryptid_account) = cryptid_account.as_mut() {
+        cryptid_account.close_transaction(
+            transaction_account.sequence,
+            transaction_account.is_expired(&clock),
+        )?;
+        cryptid_account.save(cryptid_account_info)?;
+    }
+
+    emit!(TransactionClosed {
+        cryptid_account: ctx.accounts.cryptid_account.key(),
+        did: ctx.accounts.transaction_account.did,
+        transaction_account: ctx.accounts.transaction_account.key(),
+        authority: None,
+        destination: ctx.accounts.payer.key(),
+    });
+
+    Ok(())
+}
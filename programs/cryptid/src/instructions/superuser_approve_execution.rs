@@ -1,4 +1,6 @@
 use crate::error::CryptidError;
+use crate::events::SuperuserApproved;
+use crate::state::approval_registry::ApprovalRegistry;
 use crate::state::cryptid_account::CryptidAccount;
 use crate::state::transaction_account::TransactionAccount;
 use crate::state::transaction_state::TransactionState;
@@ -6,6 +8,11 @@ use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
 pub struct SuperuserApproveExecution<'info> {
+    /// The middleware account approving the transaction.
+    /// Must be owned by a program in the approval registry
+    #[account(
+        constraint = approval_registry.is_registered(middleware_account.owner) @ CryptidError::UnregisteredMiddleware,
+    )]
     pub middleware_account: Signer<'info>,
     #[account(
         mut,
@@ -15,6 +22,12 @@ pub struct SuperuserApproveExecution<'info> {
         constraint = !transaction_account.authorized @ CryptidError::AlreadyAuthorizedTransactionAccount,
     )]
     pub transaction_account: Account<'info, TransactionAccount>,
+    /// The registry of middleware programs that can approve transactions
+    #[account(
+        seeds = [ApprovalRegistry::SEED_PREFIX],
+        bump,
+    )]
+    pub approval_registry: Account<'info, ApprovalRegistry>,
     #[account(
         // This instruction can only be used if the CryptidAccount allows it.
         constraint = cryptid_account.superuser_middleware.contains(middleware_account.key) @ CryptidError::IncorrectSuperuserMiddleware,
@@ -25,21 +38,22 @@ pub struct SuperuserApproveExecution<'info> {
 pub fn superuser_approve_execution<'info>(
     ctx: Context<'_, '_, '_, 'info, SuperuserApproveExecution<'info>>,
 ) -> Result<()> {
-    // Make sure owner is NOT the System Program
-    // TODO(ticket): Consider implementing an approval registry on middleware
-    require!(
-        !anchor_lang::solana_program::system_program::check_id(
-            ctx.accounts.middleware_account.owner
-        ),
-        CryptidError::InvalidMiddlewareAccount
-    );
+    ctx.accounts.transaction_account.check_not_expired()?;
 
     msg!(
         "Transaction approved by middleware owned by program: {}",
         ctx.accounts.middleware_account.owner
     );
-    ctx.accounts.transaction_account.approved_middleware =
-        Some(*ctx.accounts.middleware_account.key);
+    let data_len = ctx
+        .accounts
+        .transaction_account
+        .to_account_info()
+        .data_len();
+    ctx.accounts.transaction_account.approve_middleware(
+        ctx.accounts.middleware_account.key,
+        &ctx.accounts.cryptid_account.middlewares,
+        data_len,
+    )?;
 
     // only the LAST superuser_middleware is able to authorize the transaction
     if ctx
@@ -53,5 +67,14 @@ pub fn superuser_approve_execution<'info>(
         ctx.accounts.transaction_account.authorized = true;
     }
 
+    emit!(SuperuserApproved {
+        cryptid_account: ctx.accounts.cryptid_account.key(),
+        did: ctx.accounts.transaction_account.did,
+        transaction_account: ctx.accounts.transaction_account.key(),
+        middleware_account: ctx.accounts.middleware_account.key(),
+        middleware_program: *ctx.accounts.middleware_account.owner,
+        authorized: ctx.accounts.transaction_account.authorized,
+    });
+
     Ok(())
 }
@@ -0,0 +1,45 @@
+use crate::error::CryptidError;
+use crate::state::approval_registry::ApprovalRegistry;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(
+/// Middleware programs to register
+add_middleware_programs: Vec<Pubkey>,
+/// Middleware programs to deregister
+remove_middleware_programs: Vec<Pubkey>,
+)]
+pub struct UpdateApprovalRegistry<'info> {
+    #[account(
+    mut,
+    seeds = [ApprovalRegistry::SEED_PREFIX],
+    bump,
+    has_one = authority @ CryptidError::NotRegistryAuthority,
+    // resize the registry to fit the new set of middleware programs
+    realloc = 8 + ApprovalRegistry::calculate_size(
+        approval_registry.updated_middleware_programs(
+            &add_middleware_programs,
+            &remove_middleware_programs
+        ).len()
+    ),
+    realloc::payer = authority,
+    realloc::zero = false,
+    )]
+    pub approval_registry: Account<'info, ApprovalRegistry>,
+    /// The authority of the registry
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Register and deregister middleware programs in the approval registry
+pub fn update_approval_registry(
+    ctx: Context<UpdateApprovalRegistry>,
+    add_middleware_programs: Vec<Pubkey>,
+    remove_middleware_programs: Vec<Pubkey>,
+) -> Result<()> {
+    let approval_registry = &mut ctx.accounts.approval_registry;
+    approval_registry.middleware_programs = approval_registry
+        .updated_middleware_programs(&add_middleware_programs, &remove_middleware_programs);
+    approval_registry.validate()
+}
@@ -0,0 +1,153 @@
+use crate::error::CryptidError;
+use crate::events::CryptidAccountUpdated;
+use crate::instructions::util::*;
+use crate::state::cryptid_account::CryptidAccount;
+use crate::util::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(
+/// The middleware pipeline of the cryptid account, in approval order, replacing the existing one
+middlewares: Vec<Pubkey>,
+/// "Superuser" middlewares to register on the cryptid account
+add_superuser_middlewares: Vec<Pubkey>,
+/// "Superuser" middlewares to deregister from the cryptid account
+remove_superuser_middlewares: Vec<Pubkey>,
+/// The number of distinct DID authorities that must sign to execute a transaction
+threshold: u8,
+/// The number of slots after which open transaction accounts can be reclaimed by anyone
+transaction_reclaim_age: Option<u64>,
+/// The controller chain between the authority and the did
+controller_chain: Vec<Pubkey>,
+/// The index of this cryptid account
+index: u32,
+/// The bump seed for the Did Account
+did_account_bump: u8,
+)]
+pub struct UpdateCryptidAccount<'info> {
+    #[account(
+    mut,
+    seeds = [CryptidAccount::SEED_PREFIX, did_program.key().as_ref(), did.key().as_ref(), index.to_le_bytes().as_ref()],
+    bump,
+    // resize the cryptid account to fit the new middlewares
+    realloc = 8 + CryptidAccount::calculate_size(
+        middlewares.len(),
+        cryptid_account.updated_superuser_middleware(
+            &add_superuser_middlewares,
+            &remove_superuser_middlewares
+        ).len()
+    ),
+    realloc::payer = authority,
+    realloc::zero = false,
+    )]
+    pub cryptid_account: Account<'info, CryptidAccount>,
+    /// The program for the DID
+    pub did_program: Program<'info, SolDID>,
+    /// The DID on the Cryptid instance
+    /// CHECK: DID Account can be generative or not
+    pub did: UncheckedAccount<'info>,
+    /// The signer of the transaction. Must be a DID authority.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Collect all accounts as a single vector, in the same order as the other instructions,
+/// so that additional signers in the remaining accounts are counted towards the threshold
+impl<'a, 'b, 'c, 'info> AllAccounts<'a, 'b, 'c, 'info>
+    for Context<'a, 'b, 'c, 'info, UpdateCryptidAccount<'info>>
+{
+    fn all_accounts(&self) -> Vec<&AccountInfo<'info>> {
+        [
+            self.accounts.cryptid_account.as_ref(),
+            self.accounts.did.as_ref(),
+            self.accounts.did_program.as_ref(),
+            self.accounts.authority.as_ref(),
+        ]
+        .into_iter()
+        .chain(self.remaining_accounts.iter())
+        .collect()
+    }
+
+    fn get_accounts_by_indexes(&self, indexes: &[u8]) -> Result<Vec<&AccountInfo<'info>>> {
+        let accounts = self.all_accounts();
+        resolve_by_index(indexes, &accounts)
+    }
+}
+
+/// Update the settings of a non-generative cryptid account.
+/// As many DID authorities as the current threshold must sign, the additional ones passed as signers
+/// in the remaining accounts, after the controller chain.
+/// Changes to the middleware must also be signed by the existing middleware (see [`CryptidAccount::check_middleware_signers`]).
+pub fn update_cryptid_account<'info>(
+    ctx: Context<'_, '_, '_, 'info, UpdateCryptidAccount<'info>>,
+    middlewares: Vec<Pubkey>,
+    add_superuser_middlewares: Vec<Pubkey>,
+    remove_superuser_middlewares: Vec<Pubkey>,
+    threshold: u8,
+    transaction_reclaim_age: Option<u64>,
+    controller_chain: Vec<Pubkey>,
+    did_account_bump: u8,
+) -> Result<()> {
+    require_gt!(threshold, 0, CryptidError::InvalidThreshold);
+
+    // convert the controller chain (an array of account indices) into an array of accounts
+    // as in create_cryptid_account, sol_did is responsible for validating the chain
+    let controlling_did_accounts = ctx
+        .remaining_accounts
+        .iter()
+        .zip(controller_chain.iter())
+        .map(|(account, pubkey)| (account, *pubkey))
+        .collect::<Vec<(&AccountInfo, Pubkey)>>();
+
+    verify_keys(
+        &ctx.accounts.did,
+        Some(did_account_bump),
+        ctx.accounts.authority.to_account_info().key,
+        controlling_did_accounts.clone(),
+    )?;
+
+    // CHECK enough DID authorities have signed to change the settings, as for executing a transaction.
+    // Otherwise a single authority could lower the threshold and act alone.
+    verify_threshold(
+        &ctx.all_accounts(),
+        &controlling_did_accounts,
+        &ctx.accounts.did,
+        did_account_bump,
+        ctx.accounts.cryptid_account.threshold,
+        &[],
+    )?;
+
+    let superuser_middleware = ctx
+        .accounts
+        .cryptid_account
+        .updated_superuser_middleware(&add_superuser_middlewares, &remove_superuser_middlewares);
+
+    // CHECK the existing middleware approves changes to the middleware, so that it cannot be removed to bypass it
+    let cryptid_account = &ctx.accounts.cryptid_account;
+    if middlewares != cryptid_account.middlewares
+        || superuser_middleware != cryptid_account.superuser_middleware
+    {
+        let signers: Vec<Pubkey> = ctx
+            .remaining_accounts
+            .iter()
+            .filter(|account| account.is_signer)
+            .map(|account| *account.key)
+            .collect();
+        cryptid_account.check_middleware_signers(&signers)?;
+    }
+
+    CryptidAccount::check_middlewares(&middlewares)?;
+    ctx.accounts.cryptid_account.middlewares = middlewares;
+    ctx.accounts.cryptid_account.superuser_middleware = superuser_middleware;
+    ctx.accounts.cryptid_account.threshold = threshold;
+    ctx.accounts.cryptid_account.transaction_reclaim_age = transaction_reclaim_age;
+
+    emit!(CryptidAccountUpdated {
+        cryptid_account: ctx.accounts.cryptid_account.key(),
+        did: ctx.accounts.did.key(),
+        authority: ctx.accounts.authority.key(),
+    });
+
+    Ok(())
+}
@@ -1,9 +1,13 @@
 use crate::error::CryptidError;
+use crate::id;
+use crate::state::abbreviated_instruction_data::AbbreviatedInstructionData;
+use crate::state::account_meta_props::AccountMetaProps;
 use crate::state::cryptid_account::CryptidAccount;
 use crate::state::did_reference::DIDReference;
 use crate::util::SolDID;
 use anchor_lang::prelude::Error::AnchorError;
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::is_sysvar_id;
 use bitflags::bitflags;
 use sol_did::state::VerificationMethodType;
 
@@ -15,7 +19,7 @@ pub trait AllAccounts<'a, 'b, 'c, 'info> {
 
 pub fn resolve_by_index<'c, 'info>(
     indexes: &[u8],
-    accounts: &Vec<&'c AccountInfo<'info>>,
+    accounts: &[&'c AccountInfo<'info>],
 ) -> Result<Vec<&'c AccountInfo<'info>>> {
     let mut resolved_accounts = Vec::new();
     for i in indexes {
@@ -29,6 +33,25 @@ pub fn resolve_by_index<'c, 'info>(
     Ok(resolved_accounts)
 }
 
+/// Returns true if the key is an authority on the DID
+/// If the controller-chain is empty, the key must be a key on the did itself
+/// Otherwise, the key must be a key on a controller of the DID (either directly or indirectly)
+pub fn is_did_authority(
+    did: &AccountInfo<'_>,
+    did_account_bump: Option<u8>,
+    key: &Pubkey,
+    controlling_did_accounts: &[(&AccountInfo<'_>, Pubkey)],
+) -> Result<bool> {
+    sol_did::integrations::is_authority(
+        did,
+        did_account_bump,
+        controlling_did_accounts,
+        &key.to_bytes(),
+        Some(&[VerificationMethodType::Ed25519VerificationKey2018]),
+        None,
+    )
+}
+
 /// Verifies that the signer has the permission to sign for the DID
 /// If the controller-chain is empty, it expects the signer to be a key on the did itself
 /// Otherwise, the signer is a signer on a controller of the DID (either directly or indirectly)
@@ -38,13 +61,11 @@ pub fn verify_keys(
     signer: &Pubkey,
     controlling_did_accounts: Vec<(&AccountInfo<'_>, Pubkey)>,
 ) -> Result<()> {
-    let signer_is_authority = sol_did::integrations::is_authority(
+    let signer_is_authority = is_did_authority(
         did,
         did_account_bump,
+        signer,
         controlling_did_accounts.as_slice(),
-        &signer.to_bytes(),
-        Some(&[VerificationMethodType::Ed25519VerificationKey2018]),
-        None,
     )
     .map_err(|error| -> CryptidError {
         msg!("Error executing is_authority: {}", error);
@@ -58,6 +79,25 @@ pub fn verify_keys(
     Ok(())
 }
 
+/// Convert the controller chain (an array of account indices) into an array of accounts
+/// note - cryptid does not need to check that the chain is valid, or even that they are DIDs
+/// sol_did does that.
+/// Fails if the chain references an account that was not provided.
+pub fn resolve_controller_chain<'c, 'info>(
+    all_accounts: &[&'c AccountInfo<'info>],
+    controller_chain: &[DIDReference],
+) -> Result<Vec<(&'c AccountInfo<'info>, Pubkey)>> {
+    controller_chain
+        .iter()
+        .map(|controller_reference| {
+            let account = all_accounts
+                .get(controller_reference.account_index as usize)
+                .ok_or_else(|| error!(CryptidError::IndexOutOfRange))?;
+            Ok((*account, controller_reference.authority_key))
+        })
+        .collect()
+}
+
 /// Check that the authority is allowed to access the cryptid account
 /// And if so, return it
 pub fn get_cryptid_account_checked<'info>(
@@ -73,19 +113,7 @@ pub fn get_cryptid_account_checked<'info>(
     allow_unauthorized_signer: bool,
 ) -> Result<CryptidAccount> {
     // Check that the authority has permissions on the DID
-
-    // convert the controller chain (an array of account indices) into an array of accounts
-    // note - cryptid does not need to check that the chain is valid, or even that they are DIDs
-    // sol_did does that.
-    let controlling_did_accounts = controller_chain
-        .iter()
-        .map(|controller_reference| {
-            (
-                all_accounts[controller_reference.account_index as usize],
-                controller_reference.authority_key,
-            )
-        })
-        .collect::<Vec<(&AccountInfo, Pubkey)>>();
+    let controlling_did_accounts = resolve_controller_chain(all_accounts, controller_chain)?;
 
     // Perform seed verification here
     let cryptid_account_obj = CryptidAccount::try_from(
@@ -97,7 +125,8 @@ pub fn get_cryptid_account_checked<'info>(
     )?;
 
     // Assume at this point that anchor has verified the cryptid account and did account (but not the controller chain)
-    // We now need to verify that the signer (at the moment, only one is supported) is a valid signer for the cryptid account
+    // We now need to verify that the signer is a valid signer for the cryptid account
+    // Additional signers, if the cryptid account requires them, are checked by verify_threshold
     verify_keys(
         did,
         Some(did_account_bump),
@@ -127,17 +156,417 @@ pub fn get_cryptid_account_checked<'info>(
     Ok(cryptid_account_obj)
 }
 
+/// Check that the accounts passed to execute a transaction are exactly those it was proposed with.
+/// The authority at AUTHORITY_ACCOUNT_INDEX is allowed to change, as any valid authority for the DID
+/// can execute the transaction.
+/// They may be followed by signers only, the additional DID authorities counted by [`verify_threshold`].
+/// Returns the accounts the transaction was proposed with, the only ones to be passed to its instructions.
+pub fn check_transaction_accounts<'a, 'b, 'info>(
+    accounts: &'a [&'b AccountInfo<'info>],
+    proposed_accounts: &[Pubkey],
+) -> Result<&'a [&'b AccountInfo<'info>]> {
+    require_gte!(
+        accounts.len(),
+        proposed_accounts.len(),
+        CryptidError::InvalidAccountCount
+    );
+    let (accounts, co_signers) = accounts.split_at(proposed_accounts.len());
+    require!(
+        co_signers.iter().all(|account| account.is_signer),
+        CryptidError::InvalidAccountCount
+    );
+    for (index, (account, proposed_account)) in
+        accounts.iter().zip(proposed_accounts.iter()).enumerate()
+    {
+        if index != AUTHORITY_ACCOUNT_INDEX {
+            require_keys_eq!(
+                *account.key,
+                *proposed_account,
+                CryptidError::InvalidAccounts
+            );
+        }
+    }
+    Ok(accounts)
+}
+
+/// Check that none of `instructions` invoke the cryptid program itself.
+/// Re-entering cryptid while a transaction is executing would allow, for example,
+/// a second transaction to be executed, or the executing one to be approved or closed, part-way through.
+pub fn check_not_reentrant(
+    instructions: &[AbbreviatedInstructionData],
+    accounts: &[Pubkey],
+) -> Result<()> {
+    for (index, instruction) in instructions.iter().enumerate() {
+        if accounts.get(instruction.program_id as usize) == Some(&id()) {
+            msg!("Instruction {} invokes the cryptid program", index);
+            return err!(CryptidError::ReentrantInstruction);
+        }
+    }
+    Ok(())
+}
+
+/// Reject instructions requesting signer or writable privileges for accounts that can never be given them,
+/// so that a transaction that can never be executed is rejected before approvals are collected.
+/// - The DID account, programs and sysvars can never be signers.
+/// - Programs and sysvars can never be writable.
+///
+/// This does not guarantee that the transaction can be executed: the cryptid account is signed for
+/// by the cryptid program, but any other account may be a signer of the executing transaction
+/// (e.g. a new account created by an instruction), so is only checked by the runtime at execution.
+pub fn check_instruction_privileges(
+    instructions: &[AbbreviatedInstructionData],
+    accounts: &[&AccountInfo],
+) -> Result<()> {
+    for (index, instruction) in instructions.iter().enumerate() {
+        for meta in instruction.accounts.iter() {
+            let account = accounts
+                .get(meta.key as usize)
+                .ok_or_else(|| error!(CryptidError::IndexOutOfRange))?;
+            let props = meta.props()?;
+            let is_fixed = account.executable || is_sysvar_id(account.key);
+
+            if props.contains(AccountMetaProps::IS_SIGNER)
+                && (is_fixed || meta.key as usize == DID_ACCOUNT_INDEX)
+            {
+                msg!(
+                    "Instruction {} requires account {} ({}) to sign, which it cannot",
+                    index,
+                    meta.key,
+                    account.key
+                );
+                return err!(CryptidError::UnsatisfiableSigner);
+            }
+            if props.contains(AccountMetaProps::IS_WRITABLE) && is_fixed {
+                msg!(
+                    "Instruction {} requires account {} ({}) to be writable, which it cannot be",
+                    index,
+                    meta.key,
+                    account.key
+                );
+                return err!(CryptidError::UnsatisfiableWritable);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Check that at least `threshold` distinct DID authorities have signed or approved the transaction.
+/// The authority at AUTHORITY_ACCOUNT_INDEX (verified by `get_cryptid_account_checked`) counts as the first,
+/// followed by any authorities that approved the transaction ahead of time,
+/// provided they are still authorities on the DID.
+/// Any other signer in the remaining accounts that is an authority on the DID,
+/// either directly or via `controlling_did_accounts`, counts as an additional one.
+pub fn verify_threshold(
+    all_accounts: &[&AccountInfo],
+    controlling_did_accounts: &[(&AccountInfo, Pubkey)],
+    did: &AccountInfo,
+    did_account_bump: u8,
+    threshold: u8,
+    approvers: &[Pubkey],
+) -> Result<()> {
+    // errors are treated as "not an authority", as the DID itself was already verified
+    // when checking the authority
+    let is_authority = |key: &Pubkey| {
+        matches!(
+            is_did_authority(did, Some(did_account_bump), key, &[]),
+            Ok(true)
+        ) || (!controlling_did_accounts.is_empty()
+            && matches!(
+                is_did_authority(did, Some(did_account_bump), key, controlling_did_accounts),
+                Ok(true)
+            ))
+    };
+
+    let mut authorities = vec![*all_accounts[AUTHORITY_ACCOUNT_INDEX].key];
+    let signers = all_accounts
+        .iter()
+        .skip(AUTHORITY_ACCOUNT_INDEX + 1)
+        .filter(|account| account.is_signer)
+        .map(|account| account.key);
+    for key in approvers.iter().chain(signers) {
+        if authorities.len() >= threshold as usize {
+            break;
+        }
+        // approvers are checked again, as they may have been removed from the DID since approving
+        if !authorities.contains(key) && is_authority(key) {
+            authorities.push(*key);
+        }
+    }
+    if authorities.len() >= threshold as usize {
+        return Ok(());
+    }
+
+    msg!(
+        "Only {} of the required {} DID authorities signed",
+        authorities.len(),
+        threshold
+    );
+    err!(CryptidError::NotEnoughSigners)
+}
+
 bitflags! {
     /// Extra flags passed to execution instructions
     #[derive(AnchorDeserialize, AnchorSerialize)]
     pub struct ExecuteFlags: u8{
         /// Print debug logs, uses a large portion of the compute budget
         const DEBUG = 1 << 0;
+        /// Allow the instructions to change the owner, size or data of the cryptid account itself,
+        /// e.g. to deliberately migrate it. Otherwise, execution fails if the cryptid account changes.
+        const ALLOW_CRYPTID_ACCOUNT_MIGRATION = 1 << 1;
+    }
+}
+impl ExecuteFlags {
+    /// Parse the flags passed to an execution instruction, failing on unknown flags
+    pub fn parse(flags: u8) -> Result<Self> {
+        Self::from_bits(flags).ok_or_else(|| error!(CryptidError::InvalidExecuteFlags))
     }
 }
 
+/// The index of the DID account in the accounts array of an instruction
+pub const DID_ACCOUNT_INDEX: usize = 1;
+
 /// The index of the authority key in the accounts array of an instruction
 /// This is a slightly "special" key as it is the only one that is allowed to change
 /// between "propose" and "execute".
 /// As long as the authority is valid for the DID, any authority can sign the transaction.
 pub const AUTHORITY_ACCOUNT_INDEX: usize = 3;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::state::abbreviated_account_meta::AbbreviatedAccountMeta;
+
+    fn proposed_accounts() -> Vec<Pubkey> {
+        (0..6).map(|_| Pubkey::new_unique()).collect()
+    }
+
+    /// Calls `f` with account infos for `keys`, of which only the `signers` are signers
+    fn with_account_infos<R>(
+        keys: &[Pubkey],
+        signers: &[Pubkey],
+        f: impl FnOnce(&[&AccountInfo]) -> R,
+    ) -> R {
+        let owner = System::id();
+        let mut lamports = vec![0u64; keys.len()];
+        let mut data = vec![[0u8; 0]; keys.len()];
+        let infos: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .map(|((key, lamports), data)| {
+                AccountInfo::new(
+                    key,
+                    signers.contains(key),
+                    false,
+                    lamports,
+                    data,
+                    &owner,
+                    false,
+                    0,
+                )
+            })
+            .collect();
+        let accounts: Vec<&AccountInfo> = infos.iter().collect();
+        f(&accounts)
+    }
+
+    fn check(accounts: &[Pubkey], signers: &[Pubkey], proposed: &[Pubkey]) -> Result<usize> {
+        with_account_infos(accounts, signers, |accounts| {
+            check_transaction_accounts(accounts, proposed).map(|accounts| accounts.len())
+        })
+    }
+
+    #[test]
+    fn check_transaction_accounts_matches() {
+        let proposed = proposed_accounts();
+        assert!(check(&proposed, &[], &proposed).is_ok());
+
+        // a different authority can execute the transaction
+        let mut accounts = proposed.clone();
+        accounts[AUTHORITY_ACCOUNT_INDEX] = Pubkey::new_unique();
+        assert!(check(&accounts, &[], &proposed).is_ok());
+    }
+
+    #[test]
+    fn check_transaction_accounts_rejects_changed_accounts() {
+        let proposed = proposed_accounts();
+        let mut accounts = proposed.clone();
+        accounts[AUTHORITY_ACCOUNT_INDEX + 1] = Pubkey::new_unique();
+        assert!(check(&accounts, &[], &proposed).is_err());
+    }
+
+    #[test]
+    fn check_transaction_accounts_rejects_truncated_or_padded_accounts() {
+        let proposed = proposed_accounts();
+        assert!(check(&proposed[..proposed.len() - 1], &[], &proposed).is_err());
+
+        let mut padded = proposed.clone();
+        padded.push(Pubkey::new_unique());
+        assert!(check(&padded, &[], &proposed).is_err());
+    }
+
+    #[test]
+    fn check_transaction_accounts_allows_trailing_signers() {
+        let proposed = proposed_accounts();
+        let [co_signer, other] = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+        // co-signers are not passed on to the instructions
+        let mut accounts = proposed.clone();
+        accounts.push(co_signer);
+        assert_eq!(
+            check(&accounts, &[co_signer], &proposed).unwrap(),
+            proposed.len()
+        );
+
+        // but only signers may follow the proposed accounts
+        accounts.push(other);
+        assert!(check(&accounts, &[co_signer], &proposed).is_err());
+    }
+
+    fn instruction(program_id: u8) -> AbbreviatedInstructionData {
+        AbbreviatedInstructionData {
+            program_id,
+            accounts: vec![],
+            data: vec![],
+        }
+    }
+
+    fn account_info<'a>(
+        key: &'a Pubkey,
+        lamports: &'a mut u64,
+        data: &'a mut [u8],
+        owner: &'a Pubkey,
+        executable: bool,
+    ) -> AccountInfo<'a> {
+        AccountInfo::new(key, false, false, lamports, data, owner, executable, 0)
+    }
+
+    fn instruction_with_meta(key: u8, props: AccountMetaProps) -> AbbreviatedInstructionData {
+        AbbreviatedInstructionData {
+            program_id: 2,
+            accounts: vec![AbbreviatedAccountMeta {
+                key,
+                meta: props.bits(),
+            }],
+            data: vec![],
+        }
+    }
+
+    #[test]
+    fn check_instruction_privileges_rejects_unsatisfiable_privileges() {
+        let clock = anchor_lang::solana_program::sysvar::clock::id();
+        let owner = System::id();
+        // cryptid account, did, did program, authority, a new account, a sysvar
+        let keys: Vec<Pubkey> = (0..5)
+            .map(|_| Pubkey::new_unique())
+            .chain([clock])
+            .collect();
+        let mut lamports = [0u64; 6];
+        let mut data = [[0u8; 0]; 6];
+        let infos: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(index, ((key, lamports), data))| {
+                // only the did program is executable
+                account_info(key, lamports, data, &owner, index == 2)
+            })
+            .collect();
+        let accounts: Vec<&AccountInfo> = infos.iter().collect();
+        let signer_writable = AccountMetaProps::IS_SIGNER | AccountMetaProps::IS_WRITABLE;
+
+        // the cryptid account, the authority and other accounts can sign and be written
+        for key in [0, AUTHORITY_ACCOUNT_INDEX as u8, 4] {
+            assert!(check_instruction_privileges(
+                &[instruction_with_meta(key, signer_writable)],
+                &accounts
+            )
+            .is_ok());
+        }
+        // the did account can be written, but not sign
+        assert!(check_instruction_privileges(
+            &[instruction_with_meta(1, AccountMetaProps::IS_WRITABLE)],
+            &accounts
+        )
+        .is_ok());
+        assert!(check_instruction_privileges(
+            &[instruction_with_meta(1, AccountMetaProps::IS_SIGNER)],
+            &accounts
+        )
+        .is_err());
+        // programs and sysvars can only be read
+        for key in [2, 5] {
+            assert!(check_instruction_privileges(
+                &[instruction_with_meta(key, AccountMetaProps::empty())],
+                &accounts
+            )
+            .is_ok());
+            assert!(check_instruction_privileges(
+                &[instruction_with_meta(key, AccountMetaProps::IS_SIGNER)],
+                &accounts
+            )
+            .is_err());
+            assert!(check_instruction_privileges(
+                &[instruction_with_meta(key, AccountMetaProps::IS_WRITABLE)],
+                &accounts
+            )
+            .is_err());
+        }
+    }
+
+    #[test]
+    fn check_not_reentrant_allows_other_programs() {
+        let mut accounts = proposed_accounts();
+        accounts.push(id());
+        assert!(check_not_reentrant(&[instruction(4), instruction(5)], &accounts).is_ok());
+    }
+
+    #[test]
+    fn check_not_reentrant_rejects_nested_execution() {
+        // e.g. an execute_transaction instruction for another transaction, nested in this one
+        let mut accounts = proposed_accounts();
+        accounts.push(id());
+        let cryptid_program_index = (accounts.len() - 1) as u8;
+        assert!(check_not_reentrant(
+            &[instruction(4), instruction(cryptid_program_index)],
+            &accounts
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn resolve_controller_chain_rejects_out_of_range_indices() {
+        let keys: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+        let owner = System::id();
+        let mut lamports = [0u64; 5];
+        let mut data = [[0u8; 0]; 5];
+        let infos: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .map(|((key, lamports), data)| account_info(key, lamports, data, &owner, false))
+            .collect();
+        let accounts: Vec<&AccountInfo> = infos.iter().collect();
+        let controller = |account_index: u8| DIDReference {
+            account_index,
+            authority_key: Pubkey::new_unique(),
+        };
+
+        let chain = resolve_controller_chain(&accounts, &[controller(4)]).unwrap();
+        assert_eq!(chain[0].0.key, &keys[4]);
+        assert_eq!(
+            resolve_controller_chain(&accounts, &[controller(4), controller(5)]).unwrap_err(),
+            error!(CryptidError::IndexOutOfRange)
+        );
+    }
+
+    #[test]
+    fn execute_flags_parse_rejects_unknown_flags() {
+        assert_eq!(
+            ExecuteFlags::parse(ExecuteFlags::all().bits()).unwrap(),
+            ExecuteFlags::all()
+        );
+        assert!(ExecuteFlags::parse(1 << 7).is_err());
+    }
+}
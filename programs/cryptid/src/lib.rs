@@ -2,12 +2,12 @@
 #![allow(clippy::too_many_arguments)]
 extern crate core;
 
-#[macro_use]
-extern crate enum_display_derive;
-
 declare_id!("cryptJTh61jY5kbUmBEXyc86tBUyueBDrLuNSZWmUcs");
 
+#[cfg(feature = "client")]
+pub mod client;
 pub mod error;
+pub mod events;
 pub mod instructions;
 pub mod state;
 pub mod util;
@@ -16,6 +16,7 @@ use anchor_lang::prelude::*;
 use instructions::*;
 use state::abbreviated_instruction_data::AbbreviatedInstructionData;
 use state::did_reference::DIDReference;
+use state::transaction_expiry::TransactionExpiry;
 use state::transaction_state::TransactionState;
 
 #[program]
@@ -24,7 +25,7 @@ pub mod cryptid {
 
     pub fn create_cryptid_account(
         ctx: Context<CreateCryptidAccount>,
-        middleware: Option<Pubkey>,
+        middlewares: Vec<Pubkey>,
         superuser_middlewares: Vec<Pubkey>,
         controller_chain: Vec<Pubkey>,
         index: u32,
@@ -32,7 +33,7 @@ pub mod cryptid {
     ) -> Result<()> {
         instructions::create_cryptid_account(
             ctx,
-            middleware,
+            middlewares,
             superuser_middlewares,
             controller_chain,
             index,
@@ -40,6 +41,29 @@ pub mod cryptid {
         )
     }
 
+    pub fn update_cryptid_account<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdateCryptidAccount<'info>>,
+        middlewares: Vec<Pubkey>,
+        add_superuser_middlewares: Vec<Pubkey>,
+        remove_superuser_middlewares: Vec<Pubkey>,
+        threshold: u8,
+        transaction_reclaim_age: Option<u64>,
+        controller_chain: Vec<Pubkey>,
+        _index: u32,
+        did_account_bump: u8,
+    ) -> Result<()> {
+        instructions::update_cryptid_account(
+            ctx,
+            middlewares,
+            add_superuser_middlewares,
+            remove_superuser_middlewares,
+            threshold,
+            transaction_reclaim_age,
+            controller_chain,
+            did_account_bump,
+        )
+    }
+
     pub fn direct_execute<'info>(
         ctx: Context<'_, '_, '_, 'info, DirectExecute<'info>>,
         controller_chain: Vec<DIDReference>,
@@ -68,6 +92,8 @@ pub mod cryptid {
         did_account_bump: u8,
         state: TransactionState,
         allow_unauthorized: bool,
+        expiry: Option<TransactionExpiry>,
+        flags: u8,
         instructions: Vec<AbbreviatedInstructionData>,
         _num_accounts: u8,
     ) -> Result<()> {
@@ -79,6 +105,37 @@ pub mod cryptid {
             did_account_bump,
             state,
             allow_unauthorized,
+            expiry,
+            flags,
+            instructions,
+        )
+    }
+
+    pub fn propose_sequenced_transaction<'info>(
+        ctx: Context<'_, '_, '_, 'info, ProposeSequencedTransaction<'info>>,
+        controller_chain: Vec<DIDReference>,
+        cryptid_account_bump: u8,
+        cryptid_account_index: u32,
+        did_account_bump: u8,
+        sequence: u64,
+        state: TransactionState,
+        allow_unauthorized: bool,
+        expiry: Option<TransactionExpiry>,
+        flags: u8,
+        instructions: Vec<AbbreviatedInstructionData>,
+        _num_accounts: u8,
+    ) -> Result<()> {
+        instructions::propose_sequenced_transaction(
+            ctx,
+            controller_chain,
+            cryptid_account_bump,
+            cryptid_account_index,
+            did_account_bump,
+            sequence,
+            state,
+            allow_unauthorized,
+            expiry,
+            flags,
             instructions,
         )
     }
@@ -122,6 +179,42 @@ pub mod cryptid {
         )
     }
 
+    pub fn execute_transaction_chunk<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteTransactionChunk<'info>>,
+        controller_chain: Vec<DIDReference>,
+        cryptid_account_bump: u8,
+        cryptid_account_index: u32,
+        did_account_bump: u8,
+        flags: u8,
+        count: u8,
+    ) -> Result<()> {
+        instructions::execute_transaction_chunk(
+            ctx,
+            controller_chain,
+            cryptid_account_bump,
+            cryptid_account_index,
+            did_account_bump,
+            flags,
+            count,
+        )
+    }
+
+    pub fn approve_transaction<'info>(
+        ctx: Context<'_, '_, '_, 'info, ApproveTransaction<'info>>,
+        controller_chain: Vec<DIDReference>,
+        cryptid_account_bump: u8,
+        cryptid_account_index: u32,
+        did_account_bump: u8,
+    ) -> Result<()> {
+        instructions::approve_transaction(
+            ctx,
+            controller_chain,
+            cryptid_account_bump,
+            cryptid_account_index,
+            did_account_bump,
+        )
+    }
+
     pub fn close_transaction<'info>(
         ctx: Context<'_, '_, '_, 'info, CloseTransaction<'info>>,
         controller_chain: Vec<DIDReference>,
@@ -138,6 +231,32 @@ pub mod cryptid {
         )
     }
 
+    pub fn close_cryptid_account<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseCryptidAccount<'info>>,
+        controller_chain: Vec<DIDReference>,
+        cryptid_account_bump: u8,
+        cryptid_account_index: u32,
+        did_account_bump: u8,
+    ) -> Result<()> {
+        instructions::close_cryptid_account(
+            ctx,
+            controller_chain,
+            cryptid_account_bump,
+            cryptid_account_index,
+            did_account_bump,
+        )
+    }
+
+    pub fn migrate_cryptid_account(ctx: Context<MigrateCryptidAccount>) -> Result<()> {
+        instructions::migrate_cryptid_account(ctx)
+    }
+
+    pub fn reclaim_transaction<'info>(
+        ctx: Context<'_, '_, '_, 'info, ReclaimTransaction<'info>>,
+    ) -> Result<()> {
+        instructions::reclaim_transaction(ctx)
+    }
+
     pub fn approve_execution<'info>(
         ctx: Context<'_, '_, '_, 'info, ApproveExecution<'info>>,
     ) -> Result<()> {
@@ -149,4 +268,24 @@ pub mod cryptid {
     ) -> Result<()> {
         instructions::superuser_approve_execution(ctx)
     }
+
+    pub fn initialize_approval_registry(
+        ctx: Context<InitializeApprovalRegistry>,
+        middleware_programs: Vec<Pubkey>,
+        registry_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::initialize_approval_registry(ctx, middleware_programs, registry_authority)
+    }
+
+    pub fn update_approval_registry(
+        ctx: Context<UpdateApprovalRegistry>,
+        add_middleware_programs: Vec<Pubkey>,
+        remove_middleware_programs: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::update_approval_registry(
+            ctx,
+            add_middleware_programs,
+            remove_middleware_programs,
+        )
+    }
 }
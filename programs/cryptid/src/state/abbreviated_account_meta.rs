@@ -1,3 +1,4 @@
+use crate::error::CryptidError;
 use crate::state::account_meta_props::AccountMetaProps;
 use anchor_lang::prelude::*;
 use std::collections::HashMap;
@@ -13,12 +14,10 @@ pub struct AbbreviatedAccountMeta {
 }
 impl fmt::Display for AbbreviatedAccountMeta {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "Key: {}, meta: {} ",
-            self.key,
-            AccountMetaProps::from_bits(self.meta).unwrap()
-        )
+        match AccountMetaProps::from_bits(self.meta) {
+            Some(props) => write!(f, "Key: {}, meta: {} ", self.key, props),
+            None => write!(f, "Key: {}, meta: invalid ({}) ", self.key, self.meta),
+        }
     }
 }
 
@@ -29,26 +28,79 @@ impl AbbreviatedAccountMeta {
             + AccountMetaProps::calculate_size() //meta
     }
 
-    /// Creates a [`TransactionAccountMeta`] from a given [`AccountMeta`]
-    pub fn from_solana_account_meta(meta: AccountMeta, accounts: &HashMap<Pubkey, u8>) -> Self {
-        Self {
-            key: *accounts
-                .get(&meta.pubkey)
-                .unwrap_or_else(|| panic!("Could not find account `{}` in accounts", meta.pubkey)),
+    /// The [`AccountMetaProps`] of the account, failing if `meta` contains unknown flags
+    pub fn props(&self) -> Result<AccountMetaProps> {
+        AccountMetaProps::try_from(self.meta)
+    }
+
+    /// Creates a [`TransactionAccountMeta`] from a given [`AccountMeta`],
+    /// failing if the account is not in `accounts`
+    pub fn try_from_solana_account_meta(
+        meta: AccountMeta,
+        accounts: &HashMap<Pubkey, u8>,
+    ) -> Result<Self> {
+        let key = *accounts.get(&meta.pubkey).ok_or_else(|| {
+            msg!("Could not find account `{}` in accounts", meta.pubkey);
+            error!(CryptidError::MissingInstructionAccount)
+        })?;
+        Ok(Self {
+            key,
             meta: AccountMetaProps::new(meta.is_signer, meta.is_writable).bits(),
-        }
+        })
     }
 
-    /// Turns `self` into a [`AccountMeta`]
-    pub fn into_solana_account_meta(self, accounts: &[Pubkey]) -> AccountMeta {
-        AccountMeta {
-            pubkey: accounts[self.key as usize],
-            is_signer: AccountMetaProps::from_bits(self.meta)
-                .unwrap()
-                .contains(AccountMetaProps::IS_SIGNER),
-            is_writable: AccountMetaProps::from_bits(self.meta)
-                .unwrap()
-                .contains(AccountMetaProps::IS_WRITABLE),
-        }
+    /// Turns `self` into a [`AccountMeta`], failing if the key is out of range of `accounts`
+    /// or `meta` contains unknown flags
+    pub fn try_into_solana_account_meta(self, accounts: &[Pubkey]) -> Result<AccountMeta> {
+        let props = self.props()?;
+        Ok(AccountMeta {
+            pubkey: *accounts
+                .get(self.key as usize)
+                .ok_or_else(|| error!(CryptidError::IndexOutOfRange))?,
+            is_signer: props.contains(AccountMetaProps::IS_SIGNER),
+            is_writable: props.contains(AccountMetaProps::IS_WRITABLE),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn try_into_solana_account_meta() {
+        let accounts = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let meta = AbbreviatedAccountMeta { key: 1, meta: 2 }
+            .try_into_solana_account_meta(&accounts)
+            .unwrap();
+        assert_eq!(meta, AccountMeta::new(accounts[1], false));
+
+        assert!(AbbreviatedAccountMeta { key: 2, meta: 0 }
+            .try_into_solana_account_meta(&accounts)
+            .is_err());
+        assert!(AbbreviatedAccountMeta { key: 0, meta: 4 }
+            .try_into_solana_account_meta(&accounts)
+            .is_err());
+    }
+
+    #[test]
+    fn try_from_solana_account_meta() {
+        let key = Pubkey::new_unique();
+        let accounts = HashMap::from([(key, 3)]);
+
+        let meta = AbbreviatedAccountMeta::try_from_solana_account_meta(
+            AccountMeta::new(key, true),
+            &accounts,
+        )
+        .unwrap();
+        assert_eq!(meta.key, 3);
+        assert_eq!(meta.props().unwrap(), AccountMetaProps::all());
+
+        assert!(AbbreviatedAccountMeta::try_from_solana_account_meta(
+            AccountMeta::new(Pubkey::new_unique(), true),
+            &accounts
+        )
+        .is_err());
     }
 }
@@ -1,3 +1,4 @@
+use crate::error::CryptidError;
 use crate::state::abbreviated_account_meta::AbbreviatedAccountMeta;
 use crate::state::instruction_size::InstructionSize;
 use anchor_lang::prelude::*;
@@ -24,47 +25,82 @@ impl AbbreviatedInstructionData {
             + 4 + size.data_len as usize //data
     }
 
-    /// Creates an [`InstructionData`] from a given [`SolanaInstruction`]
-    pub fn from_instruction(instruction: Instruction, accounts: &HashMap<Pubkey, u8>) -> Self {
-        Self {
-            program_id: *accounts.get(&instruction.program_id).unwrap_or_else(|| {
-                panic!(
-                    "Could not find program `{}` in accounts",
-                    instruction.program_id
-                )
-            }),
+    /// Creates an [`InstructionData`] from a given [`SolanaInstruction`],
+    /// failing if the program or any account is not in `accounts`
+    pub fn try_from_instruction(
+        instruction: Instruction,
+        accounts: &HashMap<Pubkey, u8>,
+    ) -> Result<Self> {
+        let program_id = *accounts.get(&instruction.program_id).ok_or_else(|| {
+            msg!(
+                "Could not find program `{}` in accounts",
+                instruction.program_id
+            );
+            error!(CryptidError::MissingInstructionAccount)
+        })?;
+        Ok(Self {
+            program_id,
             accounts: instruction
                 .accounts
                 .into_iter()
-                .map(|meta| AbbreviatedAccountMeta::from_solana_account_meta(meta, accounts))
-                .collect(),
+                .map(|meta| AbbreviatedAccountMeta::try_from_solana_account_meta(meta, accounts))
+                .collect::<Result<_>>()?,
             data: instruction.data,
-        }
+        })
     }
 
-    /// Turns `self` into a [`SolanaInstruction`]
-    pub fn into_instruction(self, accounts: &[Pubkey]) -> Instruction {
-        Instruction {
-            program_id: accounts[self.program_id as usize],
+    /// Turns `self` into a [`SolanaInstruction`],
+    /// failing if an index is out of range of `accounts` or an account meta is invalid
+    pub fn try_into_instruction(self, accounts: &[Pubkey]) -> Result<Instruction> {
+        Ok(Instruction {
+            program_id: *accounts
+                .get(self.program_id as usize)
+                .ok_or_else(|| error!(CryptidError::IndexOutOfRange))?,
             accounts: self
                 .accounts
                 .into_iter()
-                .map(|meta| meta.into_solana_account_meta(accounts))
-                .collect(),
+                .map(|meta| meta.try_into_solana_account_meta(accounts))
+                .collect::<Result<_>>()?,
             data: self.data,
-        }
+        })
     }
 
-    pub fn select_account_infos<'a>(
+    /// Selects the account infos referenced by the instruction,
+    /// failing if an index is out of range of `account_infos`
+    pub fn try_select_account_infos<'a>(
         self,
         account_infos: &'a [AccountInfo<'a>],
-    ) -> Vec<AccountInfo<'a>> {
+    ) -> Result<Vec<AccountInfo<'a>>> {
         self.accounts
             .into_iter()
-            .map(|meta| account_infos[meta.key as usize].clone())
+            .map(|meta| {
+                account_infos
+                    .get(meta.key as usize)
+                    .cloned()
+                    .ok_or_else(|| error!(CryptidError::IndexOutOfRange))
+            })
             .collect()
     }
 
+    /// Checks that the instruction only references accounts within `accounts_len`,
+    /// and that all account metas are valid
+    pub fn validate(&self, accounts_len: usize) -> Result<()> {
+        require_gt!(
+            accounts_len,
+            self.program_id as usize,
+            CryptidError::IndexOutOfRange
+        );
+        for meta in self.accounts.iter() {
+            require_gt!(
+                accounts_len,
+                meta.key as usize,
+                CryptidError::IndexOutOfRange
+            );
+            meta.props()?;
+        }
+        Ok(())
+    }
+
     pub fn get_max_account_index(&self) -> u8 {
         self.accounts.iter().map(|meta| meta.key).max().unwrap_or(0)
     }
@@ -80,3 +116,45 @@ impl fmt::Display for AbbreviatedInstructionData {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn validate() {
+        let instruction = AbbreviatedInstructionData {
+            program_id: 2,
+            accounts: vec![AbbreviatedAccountMeta { key: 1, meta: 3 }],
+            data: vec![],
+        };
+        assert!(instruction.validate(3).is_ok());
+        // program id out of range
+        assert!(instruction.validate(2).is_err());
+
+        let invalid_meta = AbbreviatedInstructionData {
+            program_id: 0,
+            accounts: vec![AbbreviatedAccountMeta { key: 0, meta: 8 }],
+            data: vec![],
+        };
+        assert!(invalid_meta.validate(1).is_err());
+    }
+
+    #[test]
+    fn try_into_instruction() {
+        let accounts = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let instruction = AbbreviatedInstructionData {
+            program_id: 1,
+            accounts: vec![AbbreviatedAccountMeta { key: 0, meta: 1 }],
+            data: vec![7],
+        };
+        let solana_instruction = instruction.clone().try_into_instruction(&accounts).unwrap();
+        assert_eq!(solana_instruction.program_id, accounts[1]);
+        assert_eq!(
+            solana_instruction.accounts,
+            vec![AccountMeta::new_readonly(accounts[0], true)]
+        );
+
+        assert!(instruction.try_into_instruction(&accounts[..1]).is_err());
+    }
+}
@@ -1,3 +1,5 @@
+use crate::error::CryptidError;
+use anchor_lang::prelude::*;
 use bitflags::bitflags;
 use std::fmt;
 
@@ -25,6 +27,15 @@ impl AccountMetaProps {
         .unwrap()
     }
 }
+impl TryFrom<u8> for AccountMetaProps {
+    type Error = anchor_lang::error::Error;
+
+    /// Parses the meta bits of an [`AbbreviatedAccountMeta`](crate::state::abbreviated_account_meta::AbbreviatedAccountMeta),
+    /// rejecting unknown flags
+    fn try_from(bits: u8) -> Result<Self> {
+        Self::from_bits(bits).ok_or_else(|| error!(CryptidError::InvalidAccountMeta))
+    }
+}
 impl fmt::Display for AccountMetaProps {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
@@ -56,4 +67,13 @@ mod test {
         );
         assert_eq!(AccountMetaProps::new(true, true), AccountMetaProps::all());
     }
+
+    #[test]
+    fn account_meta_try_from_bits() {
+        assert_eq!(
+            AccountMetaProps::try_from(3).unwrap(),
+            AccountMetaProps::all()
+        );
+        assert!(AccountMetaProps::try_from(1 << 2).is_err());
+    }
 }
@@ -0,0 +1,101 @@
+use crate::error::CryptidError;
+use crate::state::updated_keys;
+use anchor_lang::prelude::*;
+
+/// The global registry of middleware programs trusted to approve transactions.
+/// Governed by an authority chosen by the upgrade authority of the cryptid program when the registry is initialized.
+#[account]
+pub struct ApprovalRegistry {
+    /// The authority that can update the registry
+    pub authority: Pubkey,
+    /// The programs whose accounts may approve transactions as middleware
+    pub middleware_programs: Vec<Pubkey>,
+}
+impl ApprovalRegistry {
+    pub const SEED_PREFIX: &'static [u8] = b"approval_registry";
+
+    /// Calculates the on-chain size of an [`ApprovalRegistry`]
+    pub fn calculate_size(middleware_program_count: usize) -> usize {
+        32 // authority
+            + 4 + 32 * middleware_program_count // middleware_programs
+    }
+
+    /// Returns true if accounts owned by `program` may approve transactions
+    pub fn is_registered(&self, program: &Pubkey) -> bool {
+        self.middleware_programs.contains(program)
+    }
+
+    /// Fails unless `signer` is the upgrade authority of the cryptid program, `upgrade_authority`.
+    /// An immutable program has no upgrade authority, so the registry must be initialized
+    /// before the program is made immutable.
+    pub fn check_initializer(upgrade_authority: Option<Pubkey>, signer: &Pubkey) -> Result<()> {
+        let upgrade_authority =
+            upgrade_authority.ok_or_else(|| error!(CryptidError::ImmutableCryptidProgram))?;
+        require_keys_eq!(
+            upgrade_authority,
+            *signer,
+            CryptidError::NotUpgradeAuthority
+        );
+        Ok(())
+    }
+
+    /// Fails if the system program is registered, as any wallet could then approve transactions
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            !self.is_registered(&System::id()),
+            CryptidError::InvalidMiddlewareAccount
+        );
+        Ok(())
+    }
+
+    /// Returns the middleware programs after registering `add` and deregistering `remove` (see [`updated_keys`]).
+    pub fn updated_middleware_programs(&self, add: &[Pubkey], remove: &[Pubkey]) -> Vec<Pubkey> {
+        updated_keys(&self.middleware_programs, add, remove)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use anchor_lang::prelude::borsh::BorshSerialize;
+
+    #[test]
+    fn calculate_size() {
+        let registry = ApprovalRegistry {
+            authority: Pubkey::new_unique(),
+            middleware_programs: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+        };
+        assert_eq!(
+            ApprovalRegistry::calculate_size(2),
+            BorshSerialize::try_to_vec(&registry).unwrap().len()
+        );
+    }
+
+    #[test]
+    fn check_initializer() {
+        let [upgrade_authority, signer] = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+        assert!(
+            ApprovalRegistry::check_initializer(Some(upgrade_authority), &upgrade_authority)
+                .is_ok()
+        );
+        assert!(ApprovalRegistry::check_initializer(Some(upgrade_authority), &signer).is_err());
+        // an immutable program cannot initialize the registry
+        assert_eq!(
+            ApprovalRegistry::check_initializer(None, &upgrade_authority).unwrap_err(),
+            error!(CryptidError::ImmutableCryptidProgram)
+        );
+    }
+
+    #[test]
+    fn is_registered() {
+        let [a, b] = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let registry = ApprovalRegistry {
+            authority: Pubkey::new_unique(),
+            middleware_programs: vec![a],
+        };
+
+        assert!(registry.is_registered(&a));
+        assert!(!registry.is_registered(&b));
+    }
+}
@@ -1,23 +1,94 @@
+use crate::state::transaction_account::DISCRIMINATOR_SIZE;
+use crate::state::updated_keys;
 use crate::{error::CryptidError, id};
 use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
 
 /// The data for an on-chain Cryptid Account
 #[account]
 #[derive(Default)]
 pub struct CryptidAccount {
-    /// The middleware, if any, used by this cryptid account
-    pub middleware: Option<Pubkey>,
+    /// The middleware pipeline of this cryptid account, in the order in which the middlewares approve transactions
+    pub middlewares: Vec<Pubkey>,
     /// The index of this cryptid account - allows multiple cryptid accounts per DID
     pub index: u32,
     /// Middlewares that have "Superuser" status on the cryptid account
     pub superuser_middleware: Vec<Pubkey>,
+    /// The number of distinct DID authorities that must sign to execute a transaction
+    pub threshold: u8,
+    /// The number of slots after which an open transaction account can be reclaimed by anyone,
+    /// refunding the rent to its payer. If None, transaction accounts can only be reclaimed once expired.
+    pub transaction_reclaim_age: Option<u64>,
+    /// The number of transaction accounts proposed for this cryptid account that have not yet been
+    /// executed or closed
+    pub open_transactions: u32,
+    /// The sequence number of the next transaction account proposed with `propose_sequenced_transaction`
+    pub next_transaction_sequence: u64,
+    /// The sequence number of the next sequenced transaction that can be executed or closed.
+    /// Sequenced transactions are executed (or closed) in the order in which they were proposed.
+    /// An expired transaction can be closed ahead of its turn, skipping any earlier ones.
+    pub next_executable_sequence: u64,
 }
 impl CryptidAccount {
     pub const SEED_PREFIX: &'static [u8] = b"cryptid_account";
 
-    pub const BASE_SIZE: usize = (1 + 32) + 4;
-    pub fn calculate_size(superuser_middleware_count: usize) -> usize {
-        Self::BASE_SIZE + 4 + (32 * superuser_middleware_count)
+    pub const BASE_SIZE: usize = 4 + 1 + (1 + 8) + 4 + 8 + 8;
+    pub fn calculate_size(middleware_count: usize, superuser_middleware_count: usize) -> usize {
+        Self::BASE_SIZE + 4 + (32 * middleware_count) + 4 + (32 * superuser_middleware_count)
+    }
+
+    /// Fails if a middleware appears more than once in a middleware pipeline
+    pub fn check_middlewares(middlewares: &[Pubkey]) -> Result<()> {
+        for (index, middleware) in middlewares.iter().enumerate() {
+            require!(
+                !middlewares[..index].contains(middleware),
+                CryptidError::DuplicateMiddleware
+            );
+        }
+        Ok(())
+    }
+
+    /// Fails unless the middleware of this cryptid account has approved a change to it, i.e. `signers` include
+    /// every middleware in the pipeline, or a superuser middleware. Cryptid accounts without a pipeline need no approval.
+    /// Otherwise, the DID authorities could remove the middleware (e.g. to then call `direct_execute`)
+    /// or close the account, without the middleware ever approving.
+    pub fn check_middleware_signers(&self, signers: &[Pubkey]) -> Result<()> {
+        require!(
+            self.middlewares.iter().all(|m| signers.contains(m))
+                || self
+                    .superuser_middleware
+                    .iter()
+                    .any(|m| signers.contains(m)),
+            CryptidError::MiddlewareApprovalRequired
+        );
+        Ok(())
+    }
+
+    /// The maximum number of distinct middlewares that can approve a transaction for this cryptid account.
+    /// Transaction accounts reserve space for this many middleware approvals when proposed.
+    pub fn max_middleware_approvals(&self) -> usize {
+        self.middlewares.len()
+            + self
+                .superuser_middleware
+                .iter()
+                .filter(|middleware| !self.middlewares.contains(middleware))
+                .count()
+    }
+
+    /// [`Self::max_middleware_approvals`] for a cryptid account that has not yet been checked.
+    /// Generative accounts (and accounts that are not cryptid accounts) have no middleware.
+    /// Fails if a cryptid-owned account cannot be deserialized.
+    pub fn max_middleware_approvals_of(cryptid_account: &AccountInfo) -> Result<usize> {
+        if cryptid_account.owner != &id() {
+            return Ok(0);
+        }
+        Account::<CryptidAccount>::try_from(cryptid_account)
+            .map(|account| account.max_middleware_approvals())
+    }
+
+    /// Returns the superuser middlewares after registering `add` and deregistering `remove` (see [`updated_keys`]).
+    pub fn updated_superuser_middleware(&self, add: &[Pubkey], remove: &[Pubkey]) -> Vec<Pubkey> {
+        updated_keys(&self.superuser_middleware, add, remove)
     }
 
     // Support generative and non-generative accounts
@@ -37,9 +108,14 @@ impl CryptidAccount {
 
         if cryptid_account.owner == &System::id() {
             return Ok(CryptidAccount {
-                middleware: None,
+                middlewares: vec![],
                 index,
                 superuser_middleware: vec![],
+                threshold: 1,
+                transaction_reclaim_age: None,
+                open_transactions: 0,
+                next_transaction_sequence: 0,
+                next_executable_sequence: 0,
             });
         }
 
@@ -47,6 +123,107 @@ impl CryptidAccount {
         let cryptid_account: Account<CryptidAccount> = Account::try_from(cryptid_account)?;
         Ok(cryptid_account.into_inner())
     }
+
+    /// Write the cryptid account data back to the account
+    /// Generative accounts carry no data, so nothing is written for them.
+    pub fn save(&self, cryptid_account: &AccountInfo) -> Result<()> {
+        if cryptid_account.owner != &id() {
+            return Ok(());
+        }
+        let mut data = cryptid_account.try_borrow_mut_data()?;
+        let mut writer: &mut [u8] = &mut data;
+        self.try_serialize(&mut writer)
+    }
+
+    /// Register a newly proposed transaction account against this cryptid account
+    pub fn open_transaction(&mut self) {
+        self.open_transactions = self.open_transactions.saturating_add(1);
+    }
+
+    /// Fails if `sequence` is that of a sequenced transaction that is not next in line to be executed.
+    /// Transactions without a sequence can be executed in any order.
+    pub fn check_transaction_sequence(&self, sequence: Option<u64>) -> Result<()> {
+        if let Some(sequence) = sequence {
+            require_eq!(
+                sequence,
+                self.next_executable_sequence,
+                CryptidError::OutOfOrderTransaction
+            );
+        }
+        Ok(())
+    }
+
+    /// Deregister a transaction account that has been executed or closed.
+    /// Saturates at zero, as transactions may have been proposed while the account was still generative.
+    /// Sequenced transactions must be closed in order, after which the next one in the sequence can be executed,
+    /// unless `is_expired`, in which case the transactions before it are skipped and can no longer be executed.
+    /// Skipped transactions can be closed at any time.
+    pub fn close_transaction(&mut self, sequence: Option<u64>, is_expired: bool) -> Result<()> {
+        if let Some(sequence) = sequence {
+            if sequence >= self.next_executable_sequence {
+                require!(
+                    sequence == self.next_executable_sequence || is_expired,
+                    CryptidError::OutOfOrderTransaction
+                );
+                self.next_executable_sequence = sequence.saturating_add(1);
+            }
+        }
+        self.open_transactions = self.open_transactions.saturating_sub(1);
+        Ok(())
+    }
+}
+
+/// The layout of cryptid accounts created before middleware pipelines, thresholds and transaction tracking
+/// were added to [`CryptidAccount`]. These can no longer be deserialized as a [`CryptidAccount`],
+/// and are upgraded with `migrate_cryptid_account`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LegacyCryptidAccount {
+    /// The middleware, if any, used by this cryptid account
+    pub middleware: Option<Pubkey>,
+    /// The index of this cryptid account
+    pub index: u32,
+    /// Middlewares that have "Superuser" status on the cryptid account
+    pub superuser_middleware: Vec<Pubkey>,
+}
+impl LegacyCryptidAccount {
+    pub const BASE_SIZE: usize = (1 + 32) + 4;
+    pub fn calculate_size(superuser_middleware_count: usize) -> usize {
+        Self::BASE_SIZE + 4 + (32 * superuser_middleware_count)
+    }
+
+    /// Deserialize a cryptid account in the legacy layout from `data`, including the discriminator.
+    /// Legacy accounts were always allocated with [`Self::calculate_size`], which no account in the current layout
+    /// matches, so this fails for accounts that have already been migrated.
+    pub fn try_deserialize(data: &[u8]) -> Result<Self> {
+        require!(
+            data.len() >= DISCRIMINATOR_SIZE
+                && data[..DISCRIMINATOR_SIZE] == CryptidAccount::DISCRIMINATOR,
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        let account = Self::deserialize(&mut &data[DISCRIMINATOR_SIZE..])
+            .map_err(|_| error!(ErrorCode::AccountDidNotDeserialize))?;
+        require_eq!(
+            data.len(),
+            DISCRIMINATOR_SIZE + Self::calculate_size(account.superuser_middleware.len()),
+            CryptidError::NotLegacyCryptidAccount
+        );
+        Ok(account)
+    }
+}
+impl From<LegacyCryptidAccount> for CryptidAccount {
+    /// Fields that did not exist in the legacy layout take the values of a newly created cryptid account
+    fn from(legacy: LegacyCryptidAccount) -> Self {
+        CryptidAccount {
+            middlewares: legacy.middleware.into_iter().collect(),
+            index: legacy.index,
+            superuser_middleware: legacy.superuser_middleware,
+            threshold: 1,
+            transaction_reclaim_age: None,
+            open_transactions: 0,
+            next_transaction_sequence: 0,
+            next_executable_sequence: 0,
+        }
+    }
 }
 
 pub fn derive_cryptid_account_with_bump(
@@ -67,3 +244,152 @@ pub fn derive_cryptid_account_with_bump(
     )
     .map_err(|_| Error::from(ErrorCode::ConstraintSeeds))
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use anchor_lang::prelude::borsh::BorshSerialize;
+
+    #[test]
+    fn check_middleware_signers() {
+        let [a, b, superuser, other] = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let mut account = CryptidAccount::default();
+
+        // no pipeline, no approval needed
+        assert!(account.check_middleware_signers(&[]).is_ok());
+
+        account.middlewares = vec![a, b];
+        account.superuser_middleware = vec![superuser];
+        assert!(account.check_middleware_signers(&[]).is_err());
+        assert!(account.check_middleware_signers(&[a, other]).is_err());
+        // every middleware in the pipeline, or a superuser, must sign
+        assert!(account.check_middleware_signers(&[b, a]).is_ok());
+        assert!(account.check_middleware_signers(&[superuser]).is_ok());
+    }
+
+    #[test]
+    fn close_transaction_in_sequence() {
+        let mut account = CryptidAccount {
+            open_transactions: 3,
+            next_transaction_sequence: 2,
+            ..Default::default()
+        };
+
+        // transactions without a sequence are not ordered
+        account.close_transaction(None, false).unwrap();
+        assert_eq!(account.next_executable_sequence, 0);
+
+        // sequenced transactions are closed in order
+        assert!(account.check_transaction_sequence(Some(1)).is_err());
+        assert!(account.close_transaction(Some(1), false).is_err());
+        account.close_transaction(Some(0), false).unwrap();
+        account.check_transaction_sequence(Some(1)).unwrap();
+        account.close_transaction(Some(1), false).unwrap();
+
+        assert_eq!(account.next_executable_sequence, 2);
+        assert_eq!(account.open_transactions, 0);
+    }
+
+    #[test]
+    fn close_expired_transaction_out_of_sequence() {
+        let mut account = CryptidAccount {
+            open_transactions: 3,
+            next_transaction_sequence: 3,
+            ..Default::default()
+        };
+
+        // an expired transaction can be closed ahead of its turn, skipping those before it
+        account.close_transaction(Some(1), true).unwrap();
+        assert_eq!(account.next_executable_sequence, 2);
+        assert!(account.check_transaction_sequence(Some(0)).is_err());
+        account.check_transaction_sequence(Some(2)).unwrap();
+
+        // the skipped transaction can still be closed, without affecting the sequence
+        account.close_transaction(Some(0), false).unwrap();
+        assert_eq!(account.next_executable_sequence, 2);
+
+        account.close_transaction(Some(2), false).unwrap();
+        assert_eq!(account.next_executable_sequence, 3);
+        assert_eq!(account.open_transactions, 0);
+    }
+
+    #[test]
+    fn migrate_legacy_account() {
+        let middleware = Pubkey::new_from_array([1; 32]);
+        let superuser = Pubkey::new_from_array([2; 32]);
+
+        // a cryptid account as written by earlier versions of the program:
+        // middleware: Option<Pubkey>, index: u32, superuser_middleware: Vec<Pubkey>
+        let mut data = CryptidAccount::DISCRIMINATOR.to_vec();
+        data.push(1);
+        data.extend_from_slice(middleware.as_ref());
+        data.extend_from_slice(&3u32.to_le_bytes());
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(superuser.as_ref());
+        assert_eq!(data.len(), 8 + LegacyCryptidAccount::calculate_size(1));
+
+        // the legacy layout cannot be read as the current layout
+        assert!(CryptidAccount::try_deserialize(&mut data.as_slice()).is_err());
+
+        let account = CryptidAccount::from(LegacyCryptidAccount::try_deserialize(&data).unwrap());
+        assert_eq!(account.middlewares, vec![middleware]);
+        assert_eq!(account.index, 3);
+        assert_eq!(account.superuser_middleware, vec![superuser]);
+        assert_eq!(account.threshold, 1);
+
+        // a legacy account without middleware keeps the space reserved for it
+        let mut data = CryptidAccount::DISCRIMINATOR.to_vec();
+        data.push(0);
+        data.extend_from_slice(&3u32.to_le_bytes());
+        data.extend_from_slice(&0u32.to_le_bytes());
+        data.resize(8 + LegacyCryptidAccount::calculate_size(0), 0);
+        let account = CryptidAccount::from(LegacyCryptidAccount::try_deserialize(&data).unwrap());
+        assert!(account.middlewares.is_empty());
+        assert_eq!(account.index, 3);
+
+        // migrated accounts are not migrated again
+        let mut migrated = vec![];
+        account.try_serialize(&mut migrated).unwrap();
+        migrated.resize(8 + CryptidAccount::calculate_size(0, 0), 0);
+        assert!(LegacyCryptidAccount::try_deserialize(&migrated).is_err());
+    }
+
+    #[test]
+    fn calculate_size() {
+        let account = CryptidAccount {
+            middlewares: vec![Default::default(); 2],
+            superuser_middleware: vec![Default::default()],
+            transaction_reclaim_age: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(
+            CryptidAccount::calculate_size(2, 1),
+            BorshSerialize::try_to_vec(&account).unwrap().len()
+        );
+    }
+
+    #[test]
+    fn max_middleware_approvals() {
+        let [a, b, c] = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let account = CryptidAccount {
+            middlewares: vec![a, b],
+            superuser_middleware: vec![b, c],
+            ..Default::default()
+        };
+
+        assert!(CryptidAccount::check_middlewares(&[a, b, c]).is_ok());
+        assert!(CryptidAccount::check_middlewares(&[a, b, a]).is_err());
+
+        // b is counted once, as it can only approve a transaction once
+        assert_eq!(account.max_middleware_approvals(), 3);
+    }
+}
@@ -1,8 +1,52 @@
 pub mod abbreviated_account_meta;
 pub mod abbreviated_instruction_data;
 pub mod account_meta_props;
+pub mod approval_registry;
 pub mod cryptid_account;
 pub mod did_reference;
 pub mod instruction_size;
 pub mod transaction_account;
+pub mod transaction_expiry;
+pub mod transaction_flags;
 pub mod transaction_state;
+
+use anchor_lang::prelude::*;
+
+/// Returns `keys` after adding `add` and removing `remove`, keeping the order of the remaining keys.
+/// Keys that are already present are not duplicated, and removing a key that is not present is a no-op.
+pub fn updated_keys(keys: &[Pubkey], add: &[Pubkey], remove: &[Pubkey]) -> Vec<Pubkey> {
+    let mut updated: Vec<Pubkey> = keys
+        .iter()
+        .filter(|key| !remove.contains(key))
+        .cloned()
+        .collect();
+    for key in add {
+        if !updated.contains(key) {
+            updated.push(*key);
+        }
+    }
+    updated
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn updated_keys() {
+        let [a, b, c] = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let keys = [a, b];
+
+        assert_eq!(super::updated_keys(&keys, &[], &[]), vec![a, b]);
+        assert_eq!(super::updated_keys(&keys, &[c], &[a]), vec![b, c]);
+        // adding an existing key does not duplicate it
+        assert_eq!(super::updated_keys(&keys, &[b, c], &[a]), vec![b, c]);
+        assert_eq!(super::updated_keys(&keys, &[b], &[]), vec![a, b]);
+        // removing an unknown key is a no-op
+        assert_eq!(super::updated_keys(&keys, &[], &[c]), vec![a, b]);
+    }
+}
@@ -1,6 +1,8 @@
 use crate::error::CryptidError;
 use crate::state::abbreviated_instruction_data::AbbreviatedInstructionData;
 use crate::state::instruction_size::InstructionSize;
+use crate::state::transaction_expiry::TransactionExpiry;
+use crate::state::transaction_flags::TransactionFlags;
 use crate::state::transaction_state::TransactionState;
 use anchor_lang::prelude::*;
 use std::fmt;
@@ -18,11 +20,14 @@ pub struct TransactionAccount {
     pub accounts: Vec<Pubkey>,
     /// The instructions that will be executed
     pub instructions: Vec<AbbreviatedInstructionData>,
-    /// The most recent middleware PDA that approved the transaction
-    pub approved_middleware: Option<Pubkey>,
+    /// The middleware PDAs that have approved the transaction.
+    /// Space is reserved for these when the transaction is proposed, see [`crate::state::cryptid_account::CryptidAccount::max_middleware_approvals`].
+    /// Approvals beyond the reserved space are rejected
+    pub approved_middlewares: Vec<Pubkey>,
     /// The slot in which the transaction was proposed
-    /// This is used to prevent replay attacks
-    pub slot: u8,
+    pub slot: u64,
+    /// The point after which the transaction can no longer be approved or executed, if any
+    pub expiry: Option<TransactionExpiry>,
     /// The transaction state, to prevent replay attacks
     /// in case an executed transaction account is not immediately
     /// garbage-collected by the runtime
@@ -33,33 +38,165 @@ pub struct TransactionAccount {
     /// it is set to to that signer, and only a `superUser` middleware can approve it.
     pub unauthorized_signer: Option<Pubkey>,
     pub authorized: bool,
+    /// The DID authorities that have approved the transaction, counting towards the cryptid account threshold
+    /// Cleared if the transaction is extended
+    pub approvers: Vec<Pubkey>,
+    /// The account that paid the rent for the transaction account when it was proposed
+    pub payer: Pubkey,
+    /// The [`TransactionFlags`] the transaction was proposed with
+    pub flags: u8,
+    /// The position of the transaction in the cryptid account's sequence,
+    /// if it was proposed with `propose_sequenced_transaction`
+    pub sequence: Option<u64>,
 }
 impl TransactionAccount {
+    pub const SEED_PREFIX: &'static [u8] = b"transaction_account";
+
+    /// The maximum number of instructions in a non-atomic transaction,
+    /// as the progress of its execution is tracked by [`TransactionState::Executing`]
+    pub const MAX_NON_ATOMIC_INSTRUCTIONS: usize = u16::MAX as usize;
+
     /// Calculates the on-chain size of a [`TransactionAccount`]
     pub fn calculate_size(
         num_accounts: usize,
         instruction_sizes: impl Iterator<Item = InstructionSize>,
+        num_approvers: usize,
+        num_middleware_approvals: usize,
     ) -> usize {
         DISCRIMINATOR_SIZE
             + 32 // cryptid_account
             + 32 // did (owner)
             + 4 + 32 * (num_accounts + 4) //accounts (+4 for the named accounts)
             + 4 + instruction_sizes.into_iter().map(AbbreviatedInstructionData::calculate_size).sum::<usize>() //transaction_instructions
-            + 1 + 32 // approved_middleware
-            + 1 // slot
-            + 1 // state
+            + 4 + 32 * num_middleware_approvals // approved_middlewares
+            + 8 // slot
+            + 1 + TransactionExpiry::calculate_size() // expiry
+            + TransactionState::calculate_size() // state
             + 1 + 32 // unauthorized signer
             + 1 // authorized
+            + 4 + 32 * num_approvers // approvers
+            + 32 // payer
+            + TransactionFlags::calculate_size() // flags
+            + 1 + 8 // sequence
     }
 
     pub fn check_account(&self, index: u8, account: &Pubkey) -> Result<()> {
+        let transaction_account = self
+            .accounts
+            .get(index as usize)
+            .ok_or_else(|| error!(CryptidError::IndexOutOfRange))?;
         require_keys_eq!(
-            self.accounts[index as usize],
+            *transaction_account,
             *account,
             CryptidError::AccountMismatch
         );
         Ok(())
     }
+
+    /// Returns true if `middleware` has approved the transaction
+    pub fn is_approved_by(&self, middleware: &Pubkey) -> bool {
+        self.approved_middlewares.contains(middleware)
+    }
+
+    /// Record the approval of the transaction by `middleware`.
+    /// If `middleware` is in the `pipeline` of the cryptid account, the middlewares before it
+    /// in the pipeline must already have approved the transaction.
+    /// Approving a transaction more than once has no further effect.
+    /// `data_len` is the size of the transaction account, which must have room for the approval,
+    /// as middlewares added to the cryptid account after the transaction was proposed have no space reserved.
+    pub fn approve_middleware(
+        &mut self,
+        middleware: &Pubkey,
+        pipeline: &[Pubkey],
+        data_len: usize,
+    ) -> Result<()> {
+        let previous_middlewares = pipeline
+            .iter()
+            .position(|m| m == middleware)
+            .map(|position| &pipeline[..position])
+            .unwrap_or_default();
+        require!(
+            previous_middlewares.iter().all(|m| self.is_approved_by(m)),
+            CryptidError::IncorrectMiddleware
+        );
+
+        if !self.is_approved_by(middleware) {
+            let required_size = Self::calculate_size(
+                // calculate_size adds the named accounts
+                self.accounts.len().saturating_sub(4),
+                InstructionSize::from_iter_to_iter(self.instructions.iter()),
+                self.approvers.len(),
+                self.approved_middlewares.len() + 1,
+            );
+            require_gte!(
+                data_len,
+                required_size,
+                CryptidError::NoSpaceForMiddlewareApproval
+            );
+            self.approved_middlewares.push(*middleware);
+        }
+        Ok(())
+    }
+
+    /// Fails unless every middleware in the `pipeline` of the cryptid account has approved the transaction
+    pub fn check_middlewares_approved(&self, pipeline: &[Pubkey]) -> Result<()> {
+        require!(
+            pipeline.iter().all(|m| self.is_approved_by(m)),
+            CryptidError::IncorrectMiddleware
+        );
+        Ok(())
+    }
+
+    /// Fails if the transaction is non-atomic and holds more instructions than its execution progress can track
+    pub fn check_instruction_count(&self) -> Result<()> {
+        if self.flags().contains(TransactionFlags::NON_ATOMIC) {
+            require_gte!(
+                Self::MAX_NON_ATOMIC_INSTRUCTIONS,
+                self.instructions.len(),
+                CryptidError::TooManyInstructions
+            );
+        }
+        Ok(())
+    }
+
+    /// The flags the transaction was proposed with
+    pub fn flags(&self) -> TransactionFlags {
+        TransactionFlags::from_bits_truncate(self.flags)
+    }
+
+    /// Returns true if the rent can be refunded to `destination` when the transaction account is closed.
+    /// Only the payer is refunded, unless they opted out when proposing the transaction.
+    pub fn is_valid_rent_destination(&self, destination: &Pubkey) -> bool {
+        *destination == self.payer
+            || self
+                .flags()
+                .contains(TransactionFlags::ANY_RENT_DESTINATION)
+    }
+
+    /// Returns true if the transaction has passed its expiry
+    pub fn is_expired(&self, clock: &Clock) -> bool {
+        self.expiry
+            .map(|expiry| expiry.is_expired(clock))
+            .unwrap_or(false)
+    }
+
+    /// Returns true if anyone can reclaim the transaction account, as it has expired,
+    /// or has been open for at least `transaction_reclaim_age` slots (see [`crate::state::cryptid_account::CryptidAccount::transaction_reclaim_age`])
+    pub fn is_reclaimable(&self, clock: &Clock, transaction_reclaim_age: Option<u64>) -> bool {
+        let is_abandoned = transaction_reclaim_age
+            .map(|age| clock.slot >= self.slot.saturating_add(age))
+            .unwrap_or(false);
+        self.is_expired(clock) || is_abandoned
+    }
+
+    /// Fails if the transaction has passed its expiry
+    pub fn check_not_expired(&self) -> Result<()> {
+        require!(
+            !self.is_expired(&Clock::get()?),
+            CryptidError::TransactionExpired
+        );
+        Ok(())
+    }
 }
 impl fmt::Display for TransactionAccount {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -81,7 +218,7 @@ mod test {
     use crate::state::abbreviated_account_meta::AbbreviatedAccountMeta;
     use crate::state::abbreviated_instruction_data::AbbreviatedInstructionData;
     use anchor_lang::prelude::borsh::BorshSerialize;
-    use std::iter::once;
+    use std::iter::{empty, once};
 
     #[test]
     fn calculate_size() {
@@ -91,26 +228,201 @@ mod test {
                 accounts: 1,
                 data_len: 1,
             }),
+            1,
+            2,
         );
         println!("Size: {size}");
 
         let account = TransactionAccount {
             cryptid_account: Default::default(),
             did: Default::default(),
-            accounts: vec![Default::default()],
+            // the named accounts are included in the transaction accounts
+            accounts: vec![Default::default(); 5],
             instructions: vec![AbbreviatedInstructionData {
                 program_id: 0,
                 accounts: vec![AbbreviatedAccountMeta { key: 0, meta: 0 }],
                 data: vec![0],
             }],
-            approved_middleware: None,
+            approved_middlewares: vec![Default::default(); 2],
             slot: 0,
-            state: TransactionState::Ready,
-            unauthorized_signer: None,
+            expiry: Some(TransactionExpiry::Slot(0)),
+            state: TransactionState::Executing { next_index: 0 },
+            unauthorized_signer: Some(Default::default()),
             authorized: true,
+            approvers: vec![Default::default()],
+            payer: Default::default(),
+            flags: 0,
+            sequence: Some(0),
         };
-        let ser_size = BorshSerialize::try_to_vec(&account).unwrap().len();
+        let ser_size = DISCRIMINATOR_SIZE + BorshSerialize::try_to_vec(&account).unwrap().len();
         println!("SerSize: {ser_size}");
         assert_eq!(size, ser_size);
     }
+
+    #[test]
+    fn is_valid_rent_destination() {
+        let payer = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let mut account = TransactionAccount {
+            cryptid_account: Default::default(),
+            did: Default::default(),
+            accounts: vec![],
+            instructions: vec![],
+            approved_middlewares: vec![],
+            slot: 0,
+            expiry: None,
+            state: TransactionState::Ready,
+            unauthorized_signer: None,
+            authorized: true,
+            approvers: vec![],
+            payer,
+            flags: 0,
+            sequence: None,
+        };
+        assert!(account.is_valid_rent_destination(&payer));
+        assert!(!account.is_valid_rent_destination(&other));
+
+        account.flags = TransactionFlags::ANY_RENT_DESTINATION.bits();
+        assert!(account.is_valid_rent_destination(&payer));
+        assert!(account.is_valid_rent_destination(&other));
+    }
+
+    #[test]
+    fn is_reclaimable() {
+        let mut account = TransactionAccount {
+            cryptid_account: Default::default(),
+            did: Default::default(),
+            accounts: vec![],
+            instructions: vec![],
+            approved_middlewares: vec![],
+            slot: 100,
+            expiry: Some(TransactionExpiry::Slot(200)),
+            state: TransactionState::Ready,
+            unauthorized_signer: None,
+            authorized: true,
+            approvers: vec![],
+            payer: Default::default(),
+            flags: 0,
+            sequence: None,
+        };
+        let clock_at = |slot| Clock {
+            slot,
+            ..Default::default()
+        };
+
+        // not expired, and without a reclaim age
+        assert!(!account.is_reclaimable(&clock_at(150), None));
+        // not expired, nor open for the reclaim age
+        assert!(!account.is_reclaimable(&clock_at(150), Some(100)));
+
+        // expired
+        assert!(account.is_reclaimable(&clock_at(201), None));
+        assert!(account.is_reclaimable(&clock_at(201), Some(1_000)));
+
+        // open for the reclaim age, but not expired
+        assert!(account.is_reclaimable(&clock_at(150), Some(50)));
+
+        // without an expiry, only the reclaim age applies
+        account.expiry = None;
+        assert!(!account.is_reclaimable(&clock_at(u64::MAX), None));
+        assert!(!account.is_reclaimable(&clock_at(199), Some(100)));
+        assert!(account.is_reclaimable(&clock_at(200), Some(100)));
+        // the reclaim age saturates rather than overflowing
+        assert!(!account.is_reclaimable(&clock_at(u64::MAX - 1), Some(u64::MAX)));
+    }
+
+    #[test]
+    fn check_instruction_count() {
+        let mut account = TransactionAccount {
+            cryptid_account: Default::default(),
+            did: Default::default(),
+            accounts: vec![],
+            instructions: vec![
+                AbbreviatedInstructionData {
+                    program_id: 0,
+                    accounts: vec![],
+                    data: vec![],
+                };
+                TransactionAccount::MAX_NON_ATOMIC_INSTRUCTIONS
+            ],
+            approved_middlewares: vec![],
+            slot: 0,
+            expiry: None,
+            state: TransactionState::Ready,
+            unauthorized_signer: None,
+            authorized: true,
+            approvers: vec![],
+            payer: Default::default(),
+            flags: TransactionFlags::NON_ATOMIC.bits(),
+            sequence: None,
+        };
+        assert!(account.check_instruction_count().is_ok());
+        assert_eq!(
+            TransactionState::executing(account.instructions.len()).unwrap(),
+            TransactionState::Executing {
+                next_index: u16::MAX
+            }
+        );
+
+        // one more instruction cannot be tracked by the execution progress
+        account.instructions.push(account.instructions[0].clone());
+        assert!(account.check_instruction_count().is_err());
+        assert!(TransactionState::executing(account.instructions.len()).is_err());
+
+        // atomic transactions are executed in one go, so are not limited
+        account.flags = 0;
+        assert!(account.check_instruction_count().is_ok());
+    }
+
+    #[test]
+    fn approve_middleware() {
+        let [a, b, superuser] = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let pipeline = [a, b];
+        let mut account = TransactionAccount {
+            cryptid_account: Default::default(),
+            did: Default::default(),
+            accounts: vec![],
+            instructions: vec![],
+            approved_middlewares: vec![],
+            slot: 0,
+            expiry: None,
+            state: TransactionState::Ready,
+            unauthorized_signer: None,
+            authorized: true,
+            approvers: vec![],
+            payer: Default::default(),
+            flags: 0,
+            sequence: None,
+        };
+
+        // space is reserved for three approvals
+        let data_len = TransactionAccount::calculate_size(0, empty(), 0, 3);
+
+        // b cannot approve before a
+        assert!(account.approve_middleware(&b, &pipeline, data_len).is_err());
+        account.approve_middleware(&a, &pipeline, data_len).unwrap();
+        assert!(account.check_middlewares_approved(&pipeline).is_err());
+
+        // approvals are not duplicated
+        account.approve_middleware(&a, &pipeline, data_len).unwrap();
+        // middlewares outside the pipeline (e.g. superusers) are not ordered
+        account
+            .approve_middleware(&superuser, &pipeline, data_len)
+            .unwrap();
+        account.approve_middleware(&b, &pipeline, data_len).unwrap();
+        assert_eq!(account.approved_middlewares, vec![a, superuser, b]);
+        assert!(account.check_middlewares_approved(&pipeline).is_ok());
+
+        // a middleware added to the pipeline after the transaction was proposed has no space
+        let c = Pubkey::new_unique();
+        assert!(account
+            .approve_middleware(&c, &[a, b, c], data_len)
+            .is_err());
+        // but repeated approvals need no further space
+        account.approve_middleware(&b, &pipeline, data_len).unwrap();
+    }
 }
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+/// The point after which a proposed [`TransactionAccount`] can no longer be approved or executed
+#[derive(Clone, Copy, Debug, AnchorDeserialize, AnchorSerialize, PartialEq, Eq)]
+pub enum TransactionExpiry {
+    /// The transaction expires after the given slot
+    Slot(u64),
+    /// The transaction expires after the given unix timestamp
+    UnixTimestamp(i64),
+}
+impl TransactionExpiry {
+    /// Calculates the on-chain size of a [`TransactionExpiry`]
+    pub const fn calculate_size() -> usize {
+        1 //enum
+            + 8 //slot or timestamp
+    }
+
+    /// Returns true if the expiry has passed at the given time
+    pub fn is_expired(&self, clock: &Clock) -> bool {
+        match self {
+            TransactionExpiry::Slot(slot) => clock.slot > *slot,
+            TransactionExpiry::UnixTimestamp(timestamp) => clock.unix_timestamp > *timestamp,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn is_expired() {
+        let clock = Clock {
+            slot: 100,
+            unix_timestamp: 1_000,
+            ..Default::default()
+        };
+
+        assert!(!TransactionExpiry::Slot(100).is_expired(&clock));
+        assert!(TransactionExpiry::Slot(99).is_expired(&clock));
+        assert!(!TransactionExpiry::UnixTimestamp(1_000).is_expired(&clock));
+        assert!(TransactionExpiry::UnixTimestamp(999).is_expired(&clock));
+    }
+}
@@ -0,0 +1,20 @@
+use bitflags::bitflags;
+
+bitflags! {
+    /// Options chosen when a transaction is proposed, stored on the [`TransactionAccount`]
+    pub struct TransactionFlags: u8 {
+        /// The rent for the transaction account may be refunded to any destination when it is
+        /// executed or closed, rather than only to the payer
+        const ANY_RENT_DESTINATION = 1 << 0;
+        /// The transaction may be executed in several chunks with `execute_transaction_chunk`,
+        /// across multiple Solana transactions. Chunks that have executed are not rolled back
+        /// if a later chunk fails, so the transaction as a whole is not atomic.
+        const NON_ATOMIC = 1 << 1;
+    }
+}
+impl TransactionFlags {
+    /// Calculates the on-chain size of a [`TransactionFlags`]
+    pub const fn calculate_size() -> usize {
+        1 //u8 size
+    }
+}
@@ -1,24 +1,65 @@
+use crate::error::CryptidError;
 use anchor_lang::prelude::*;
-use std::fmt::Display;
+use std::fmt;
 
 /// A [`TransactionAccount`]'s state
-#[derive(Clone, Debug, Display, AnchorDeserialize, AnchorSerialize, PartialEq, Eq)]
+#[derive(Clone, Debug, AnchorDeserialize, AnchorSerialize, PartialEq, Eq, Default)]
 pub enum TransactionState {
     /// Transaction account is not ready to execute - it is waiting for additional instructions to be added
+    #[default]
     NotReady,
     /// Transaction account is ready to execute
     Ready,
     /// Transaction account has executed
     Executed,
+    /// Transaction account is part-way through a non-atomic execution
+    Executing {
+        /// The index of the next instruction to execute
+        next_index: u16,
+    },
 }
 impl TransactionState {
     /// Calculates the on-chain size of a [`TransactionState`]
     pub const fn calculate_size() -> usize {
         1 //enum
+            + 2 //next_index
+    }
+
+    /// The state of a transaction whose instructions before `next_index` have executed.
+    /// Fails if `next_index` does not fit, rather than wrapping and re-executing instructions.
+    pub fn executing(next_index: usize) -> Result<Self> {
+        let next_index =
+            u16::try_from(next_index).map_err(|_| error!(CryptidError::TooManyInstructions))?;
+        Ok(Self::Executing { next_index })
+    }
+
+    /// Returns true if the transaction is yet to start executing.
+    /// Transactions can only be proposed or extended into a pending state, and those part-way through
+    /// a non-atomic execution can be neither approved nor reclaimed.
+    pub fn is_pending(&self) -> bool {
+        matches!(self, Self::NotReady | Self::Ready)
     }
 }
-impl Default for TransactionState {
-    fn default() -> Self {
-        Self::NotReady
+impl fmt::Display for TransactionState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotReady => write!(f, "NotReady"),
+            Self::Ready => write!(f, "Ready"),
+            Self::Executed => write!(f, "Executed"),
+            Self::Executing { next_index } => write!(f, "Executing (next index: {next_index})"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn is_pending() {
+        assert!(TransactionState::NotReady.is_pending());
+        assert!(TransactionState::Ready.is_pending());
+        assert!(!TransactionState::Executing { next_index: 1 }.is_pending());
+        assert!(!TransactionState::Executed.is_pending());
     }
 }
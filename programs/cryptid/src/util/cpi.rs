@@ -1,32 +1,99 @@
 use crate::error::CryptidError;
 use crate::id;
-use crate::instructions::util::resolve_by_index;
+use crate::instructions::util::{resolve_by_index, ExecuteFlags};
 use crate::state::cryptid_account::CryptidAccount;
 use crate::util::seeder::*;
 use crate::AbbreviatedInstructionData;
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::instruction::Instruction;
 use anchor_lang::solana_program::log::sol_log_compute_units;
-use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::solana_program::program::{
+    get_return_data, invoke, invoke_signed, set_return_data,
+};
 use anchor_lang::solana_program::program_utils::limited_deserialize;
+use anchor_lang::solana_program::system_instruction;
 use anchor_lang::solana_program::system_instruction::SystemInstruction;
 
-/// The first byte of a transfer instruction is the instruction type
+/// The first byte of a system instruction is the instruction type
 /// as defined by the system program
 /// https://docs.rs/solana-sdk/1.4.9/solana_sdk/system_instruction/enum.SystemInstruction.html
+pub const CREATE_ACCOUNT_INSTRUCTION_INDEX: u8 = 0;
 pub const TRANSFER_INSTRUCTION_INDEX: u8 = 2;
+pub const CREATE_ACCOUNT_WITH_SEED_INSTRUCTION_INDEX: u8 = 3;
 
-pub fn is_transfer(solana_instruction: &Instruction) -> bool {
+/// True if `solana_instruction` is a system instruction that funds an account from its first account,
+/// i.e. a transfer, or the creation of a new account.
+pub fn is_lamport_debit(solana_instruction: &Instruction) -> bool {
     solana_instruction.program_id == System::id()
-        && solana_instruction.data[0] == TRANSFER_INSTRUCTION_INDEX
+        && matches!(
+            solana_instruction.data.first(),
+            Some(
+                &CREATE_ACCOUNT_INSTRUCTION_INDEX
+                    | &TRANSFER_INSTRUCTION_INDEX
+                    | &CREATE_ACCOUNT_WITH_SEED_INSTRUCTION_INDEX
+            )
+        )
+}
+
+/// The owner and data of the cryptid account before executing instructions
+struct CryptidAccountSnapshot {
+    owner: Pubkey,
+    data: Vec<u8>,
+}
+impl CryptidAccountSnapshot {
+    fn take(cryptid_account_info: &AccountInfo) -> Self {
+        Self {
+            owner: *cryptid_account_info.owner,
+            data: cryptid_account_info.data.borrow().to_vec(),
+        }
+    }
+
+    /// Fails if the cryptid account has been reassigned, resized or its data changed since the snapshot,
+    /// unless `allow_migration` is set.
+    /// A cryptid account owned by the cryptid program must also remain rent-exempt.
+    fn check(
+        &self,
+        cryptid_account_info: &AccountInfo,
+        rent: &Rent,
+        allow_migration: bool,
+    ) -> Result<()> {
+        if !allow_migration {
+            require!(
+                *cryptid_account_info.owner == self.owner
+                    && *cryptid_account_info.data.borrow() == self.data.as_slice(),
+                CryptidError::CryptidAccountModified
+            );
+        }
+        if cryptid_account_info.owner == &id() {
+            require!(
+                rent.is_exempt(
+                    cryptid_account_info.lamports(),
+                    cryptid_account_info.data_len()
+                ),
+                CryptidError::CryptidAccountNotRentExempt
+            );
+        }
+        Ok(())
+    }
 }
 
 pub struct CPI {}
 impl CPI {
+    /// Execute `instructions`, signing for the cryptid account where required.
+    /// The return data of the last sub-instruction, including any set by a program it invoked,
+    /// is forwarded as the return data of cryptid. If the last sub-instruction sets none, cryptid returns none.
+    /// `first_index` is the position of the first of `instructions` in the transaction, used when
+    /// reporting the index of an instruction, if only part of a transaction is executed.
+    /// Fails if the instructions change the cryptid account itself, unless `flags` allows migration.
+    ///
+    /// The index and program of each sub-instruction are logged before it is invoked.
+    /// A failed CPI aborts the whole transaction with the program's error, without returning here,
+    /// so the failing sub-instruction is the last one logged.
     #[allow(clippy::too_many_arguments)]
     pub fn execute_instructions(
-        instructions: &Vec<AbbreviatedInstructionData>,
-        accounts: &Vec<&AccountInfo>,
+        instructions: &[AbbreviatedInstructionData],
+        first_index: usize,
+        accounts: &[&AccountInfo],
         did_program: &Pubkey,
         did: &Pubkey,
         // TODO(ticket): Simplify if generative Accounts are supported
@@ -39,10 +106,14 @@ impl CPI {
         cryptid_account: &CryptidAccount,
         cryptid_account_info: &AccountInfo,
         bump: u8,
-        debug: bool,
+        flags: ExecuteFlags,
     ) -> Result<()> {
+        let debug = flags.contains(ExecuteFlags::DEBUG);
         let all_keys_vec = accounts.iter().map(|a| *a.key).collect::<Vec<_>>();
 
+        // The cryptid account must not be changed by the sub-instructions
+        let snapshot = CryptidAccountSnapshot::take(cryptid_account_info);
+
         let seeder = Box::new(CryptidSeeder {
             did_program: *did_program,
             did: *did,
@@ -52,6 +123,7 @@ impl CPI {
 
         // Generate and Execute instructions
         for (index, instruction_data) in instructions.iter().enumerate() {
+            let index = first_index + index;
             if debug {
                 msg!(
                     "Executing instruction {} program {} accounts {:?}",
@@ -67,12 +139,20 @@ impl CPI {
                 msg!(
                     "Remaining compute units for sub-instruction `{}` of {}",
                     index,
-                    instructions.len()
+                    first_index + instructions.len()
                 );
                 sol_log_compute_units();
             }
 
-            let solana_instruction = instruction_data.clone().into_instruction(&all_keys_vec[..]);
+            let solana_instruction = instruction_data
+                .clone()
+                .try_into_instruction(&all_keys_vec[..])?;
+            // Guard against re-entering cryptid while the transaction is executing
+            require_keys_neq!(
+                solana_instruction.program_id,
+                id(),
+                CryptidError::ReentrantInstruction
+            );
             let account_indexes = instruction_data
                 .accounts
                 .iter()
@@ -83,6 +163,15 @@ impl CPI {
                 .cloned()
                 .collect::<Vec<_>>();
 
+            msg!(
+                "Executing sub-instruction {} (program {})",
+                index,
+                solana_instruction.program_id
+            );
+
+            // Clear the return data of the previous sub-instruction, so that it is not mistaken for this one's
+            set_return_data(&[]);
+
             // Check if the instruction needs cryptid to sign it
             let is_signed_by_cryptid = solana_instruction
                 .accounts
@@ -90,52 +179,77 @@ impl CPI {
                 .any(|meta| meta.pubkey.eq(cryptid_account_info.key) && meta.is_signer);
 
             let sub_instruction_result = if is_signed_by_cryptid {
-                // special case on Cryptid with a transfer instruction
+                let seeds = seeder.seeds();
+                // Turn Vec<Vec<u8>> into &[&[u8]]
+                let seeds_slices_vec: Vec<&[u8]> = seeds.iter().map(|x| &x[..]).collect();
+
+                // special case on Cryptid with a transfer or create account instruction
                 if Self::is_native_transfer_needed(cryptid_account_info, &solana_instruction) {
-                    // short-circuit for transfer instructions (see comment on execute_transfer)
+                    // short-circuit for instructions debiting cryptid (see comment on execute_safe_transfer)
                     Self::execute_safe_transfer(
                         cryptid_account_info,
                         instruction_data,
                         &solana_instruction,
                         &account_infos,
+                        &seeds_slices_vec,
                     )
                 } else {
-                    let seeds = seeder.seeds();
                     if debug {
                         msg!("Invoking signed with seeds: {:?}", seeds);
                     }
 
-                    // Turn Vec<Vec<u8>> into &[&[u8]]
-                    let seeds_slices_vec: Vec<&[u8]> = seeds.iter().map(|x| &x[..]).collect();
-
                     invoke_signed(
                         &solana_instruction,
                         account_infos.as_slice(),
                         &[&seeds_slices_vec[..]],
                     )
-                    .map_err(|_| error!(CryptidError::SubInstructionError))
+                    .map_err(Into::into)
                 }
             } else {
                 msg!("Invoking without signature");
                 // TODO: IDCOM-2103: Add tests
-                invoke(&solana_instruction, account_infos.as_slice())
-                    .map_err(|_| error!(CryptidError::SubInstructionError))
+                invoke(&solana_instruction, account_infos.as_slice()).map_err(Into::into)
             };
 
-            // If sub-instruction errored log the index and error
+            // If a sub-instruction executed by cryptid itself errored, log the index, program and error
             if let Err(ref error) = sub_instruction_result {
-                msg!("Error in sub-instruction `{}`: {:?}", index, error);
+                msg!(
+                    "Error in sub-instruction `{}` (program {}): {:?}",
+                    index,
+                    solana_instruction.program_id,
+                    error
+                );
                 return sub_instruction_result;
             }
         }
+
+        // CHECK the sub-instructions have not corrupted the cryptid account
+        snapshot.check(
+            cryptid_account_info,
+            &Rent::get()?,
+            flags.contains(ExecuteFlags::ALLOW_CRYPTID_ACCOUNT_MIGRATION),
+        )?;
+
+        // Forward the return data of the last sub-instruction, so that callers of cryptid can read it
+        if let Some((_, data)) = get_return_data() {
+            set_return_data(&data);
+        }
         Ok(())
     }
 
+    /// True if `instruction` debits a cryptid account that carries data (see [`Self::execute_safe_transfer`]).
+    /// Instructions funded by another account, e.g. a `CreateAccountWithSeed` with cryptid as the base only,
+    /// are executed as a normal CPI.
     fn is_native_transfer_needed(
         cryptid_account_info: &AccountInfo,
         instruction: &Instruction,
     ) -> bool {
-        is_transfer(instruction) && cryptid_account_info.owner == &id()
+        is_lamport_debit(instruction)
+            && cryptid_account_info.owner == &id()
+            && matches!(
+                instruction.accounts.first(),
+                Some(funder) if funder.pubkey == *cryptid_account_info.key
+            )
     }
 
     /// SOL Transfer instructions cannot be executed as CPIs from PDAs with data.
@@ -144,51 +258,244 @@ impl CPI {
     /// ` 'Transfer: `from` must not carry data',`
     /// See also: https://www.reddit.com/r/solanadev/comments/rnodg2/sol_escrow_pda_with_anchor/
     /// so if this is a non-generative cryptid account, we need to do it manually.
+    ///
+    /// The same applies to `CreateAccount` and `CreateAccountWithSeed`, which are executed by
+    /// funding the new account directly, then allocating and assigning it with the system program.
+    /// (`TransferWithSeed` debits the derived account rather than its base, so is executed as a normal CPI.)
     fn execute_safe_transfer(
         cryptid_account_info: &AccountInfo,
         instruction_data: &AbbreviatedInstructionData,
         solana_instruction: &Instruction,
         account_infos: &[AccountInfo],
+        seeds: &[&[u8]],
     ) -> Result<()> {
-        // The sender must be the cryptid account.
-        require_keys_eq!(
-            account_infos[0].key(),
-            cryptid_account_info.key(),
-            CryptidError::AccountMismatch
-        );
+        // The sender is the cryptid account (see is_native_transfer_needed).
         // The cryptid account must be marked as a signer (even though it is a PDA)
         require!(
             solana_instruction.accounts[0].is_signer,
             CryptidError::KeyMustBeSigner
         );
-        // the second account is the recipient
+        // the second account is the recipient (or the new account)
         let recipient = account_infos
             .get(1)
             .ok_or(CryptidError::SubInstructionError)?;
 
-        Self::execute_transfer_unchecked(instruction_data, cryptid_account_info, recipient)
-    }
-
-    fn execute_transfer_unchecked(
-        instruction: &AbbreviatedInstructionData,
-        cryptid_account: &AccountInfo,
-        recipient: &AccountInfo,
-    ) -> Result<()> {
         let deserialized_instruction =
-            limited_deserialize(&instruction.data, instruction.data.len() as u64)
+            limited_deserialize(&instruction_data.data, instruction_data.data.len() as u64)
                 .map_err(|_| CryptidError::SubInstructionError)?;
         match deserialized_instruction {
             SystemInstruction::Transfer { lamports } => {
-                // Safe, as long as overflow-checks = true is set in cargo.toml
-                let mut account_lamports = cryptid_account.try_borrow_mut_lamports()?;
-                **account_lamports -= lamports;
+                Self::execute_transfer_unchecked(cryptid_account_info, recipient, lamports)
+            }
+            SystemInstruction::CreateAccount {
+                lamports,
+                space,
+                owner,
+            } => {
+                // as in the system program, the new account must not be in use
+                require_eq!(recipient.lamports(), 0, CryptidError::AccountAlreadyInUse);
+                Self::execute_transfer_unchecked(cryptid_account_info, recipient, lamports)?;
+
+                // the new account signs the allocation and assignment itself
+                let new_account = [recipient.clone()];
+                invoke(
+                    &system_instruction::allocate(recipient.key, space),
+                    &new_account,
+                )?;
+                invoke(
+                    &system_instruction::assign(recipient.key, &owner),
+                    &new_account,
+                )?;
+                Ok(())
+            }
+            SystemInstruction::CreateAccountWithSeed {
+                base,
+                seed,
+                lamports,
+                space,
+                owner,
+            } => {
+                require_eq!(recipient.lamports(), 0, CryptidError::AccountAlreadyInUse);
+                // the base signs for the new account. It is omitted from the accounts if it is cryptid
+                let base_info = account_infos
+                    .iter()
+                    .find(|account| account.key == &base)
+                    .ok_or(CryptidError::AccountMismatch)?;
+                Self::execute_transfer_unchecked(cryptid_account_info, recipient, lamports)?;
 
-                let mut recipient_lamports = recipient.try_borrow_mut_lamports()?;
-                **recipient_lamports += lamports;
+                // the system program checks the new account is derived from the base and seed
+                let new_account = [recipient.clone(), base_info.clone()];
+                invoke_signed(
+                    &system_instruction::allocate_with_seed(
+                        recipient.key,
+                        &base,
+                        &seed,
+                        space,
+                        &owner,
+                    ),
+                    &new_account,
+                    &[seeds],
+                )?;
+                invoke_signed(
+                    &system_instruction::assign_with_seed(recipient.key, &base, &seed, &owner),
+                    &new_account,
+                    &[seeds],
+                )?;
+                Ok(())
             }
-            _ => return Err(CryptidError::SubInstructionError.into()),
+            _ => Err(CryptidError::SubInstructionError.into()),
         }
+    }
+
+    fn execute_transfer_unchecked(
+        cryptid_account: &AccountInfo,
+        recipient: &AccountInfo,
+        lamports: u64,
+    ) -> Result<()> {
+        // The cryptid account carries data, so must keep enough lamports to stay rent-exempt
+        let minimum_balance = Rent::get()?.minimum_balance(cryptid_account.data_len());
+        let mut account_lamports = cryptid_account.try_borrow_mut_lamports()?;
+        let remaining_lamports = account_lamports
+            .checked_sub(lamports)
+            .filter(|remaining| *remaining >= minimum_balance)
+            .ok_or(CryptidError::CryptidAccountNotRentExempt)?;
+        **account_lamports = remaining_lamports;
+
+        let mut recipient_lamports = recipient.try_borrow_mut_lamports()?;
+        **recipient_lamports += lamports;
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn is_lamport_debit_matches_funding_system_instructions() {
+        let from = Pubkey::new_unique();
+        let to = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+
+        assert!(is_lamport_debit(&system_instruction::transfer(
+            &from, &to, 1
+        )));
+        assert!(is_lamport_debit(&system_instruction::create_account(
+            &from, &to, 1, 0, &owner
+        )));
+        assert!(is_lamport_debit(
+            &system_instruction::create_account_with_seed(&from, &to, &from, "seed", 1, 0, &owner)
+        ));
+
+        assert!(!is_lamport_debit(&system_instruction::assign(&to, &owner)));
+        assert!(!is_lamport_debit(&system_instruction::transfer_with_seed(
+            &to,
+            &from,
+            "seed".to_string(),
+            &owner,
+            &from,
+            1
+        )));
+        // not a system instruction
+        assert!(!is_lamport_debit(&Instruction::new_with_bytes(
+            owner,
+            &[TRANSFER_INSTRUCTION_INDEX],
+            vec![]
+        )));
+    }
+
+    #[test]
+    fn is_native_transfer_needed_only_when_cryptid_funds() {
+        let key = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let to = Pubkey::new_unique();
+        let owner = id();
+        let mut data = vec![0; 10];
+        let mut lamports = 0;
+        let account = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            0,
+        );
+
+        assert!(CPI::is_native_transfer_needed(
+            &account,
+            &system_instruction::transfer(&key, &to, 1)
+        ));
+        assert!(CPI::is_native_transfer_needed(
+            &account,
+            &system_instruction::create_account_with_seed(&key, &to, &key, "seed", 1, 0, &owner)
+        ));
+
+        // funded by another signer
+        assert!(!CPI::is_native_transfer_needed(
+            &account,
+            &system_instruction::create_account(&other, &key, 1, 0, &owner)
+        ));
+        // cryptid is only the base
+        assert!(!CPI::is_native_transfer_needed(
+            &account,
+            &system_instruction::create_account_with_seed(&other, &to, &key, "seed", 1, 0, &owner)
+        ));
+
+        // a generative cryptid account carries no data, so can transfer through the system program
+        let system_owner = System::id();
+        let generative = AccountInfo {
+            owner: &system_owner,
+            ..account
+        };
+        assert!(!CPI::is_native_transfer_needed(
+            &generative,
+            &system_instruction::transfer(&key, &to, 1)
+        ));
+    }
+
+    #[test]
+    fn cryptid_account_snapshot() {
+        let key = Pubkey::new_unique();
+        let owner = id();
+        let rent = Rent::default();
+        let mut data = vec![0; 10];
+        let mut lamports = rent.minimum_balance(data.len());
+        let account = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            0,
+        );
+        let snapshot = CryptidAccountSnapshot::take(&account);
+
+        assert!(snapshot.check(&account, &rent, false).is_ok());
+
+        // below the rent-exempt minimum
+        **account.try_borrow_mut_lamports().unwrap() -= 1;
+        assert!(snapshot.check(&account, &rent, false).is_err());
+        assert!(snapshot.check(&account, &rent, true).is_err());
+        **account.try_borrow_mut_lamports().unwrap() += 1;
+
+        // data changed
+        account.try_borrow_mut_data().unwrap()[0] = 1;
+        assert!(snapshot.check(&account, &rent, false).is_err());
+        assert!(snapshot.check(&account, &rent, true).is_ok());
+        account.try_borrow_mut_data().unwrap()[0] = 0;
+
+        // reassigned
+        let system_owner = System::id();
+        let reassigned = AccountInfo {
+            owner: &system_owner,
+            ..account
+        };
+        assert!(snapshot.check(&reassigned, &rent, false).is_err());
+        assert!(snapshot.check(&reassigned, &rent, true).is_ok());
+    }
+}
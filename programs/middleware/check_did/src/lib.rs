@@ -40,17 +40,12 @@ pub mod check_did {
         if let Some(required_previous_middleware) =
             ctx.accounts.middleware_account.previous_middleware
         {
-            match ctx.accounts.transaction_account.approved_middleware {
-                None => err!(CryptidError::IncorrectMiddleware),
-                Some(approved_previous_middleware) => {
-                    require_keys_eq!(
-                        required_previous_middleware,
-                        approved_previous_middleware,
-                        CryptidError::IncorrectMiddleware
-                    );
-                    Ok(())
-                }
-            }?;
+            require!(
+                ctx.accounts
+                    .transaction_account
+                    .is_approved_by(&required_previous_middleware),
+                CryptidError::IncorrectMiddleware
+            );
         }
 
         let did = &ctx.accounts.did;
@@ -151,6 +146,12 @@ pub struct ExecuteMiddleware<'info> {
     /// Must be owned by the owner of the transaction
     /// CHECK: Constraints are checked by the gateway sdk
     pub cryptid_program: Program<'info, Cryptid>,
+    /// The registry of middleware programs that can approve cryptid transactions
+    /// CHECK: The registry is checked by the cryptid program
+    pub approval_registry: UncheckedAccount<'info>,
+    /// The cryptid account the transaction was proposed for
+    /// CHECK: The cryptid account is checked by the cryptid program
+    pub cryptid_account: UncheckedAccount<'info>,
 }
 
 impl<'info> ExecuteMiddleware<'info> {
@@ -159,6 +160,8 @@ impl<'info> ExecuteMiddleware<'info> {
         let cpi_accounts = ApproveExecution {
             middleware_account: ctx.accounts.middleware_account.to_account_info(),
             transaction_account: ctx.accounts.transaction_account.to_account_info(),
+            approval_registry: ctx.accounts.approval_registry.to_account_info(),
+            cryptid_account: ctx.accounts.cryptid_account.to_account_info(),
         };
         // define seeds inline here rather than extract to a function
         // in order to avoid having to convert Vec<Vec<u8>> to &[&[u8]]
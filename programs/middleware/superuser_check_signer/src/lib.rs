@@ -38,17 +38,12 @@ pub mod superuser_check_signer {
         if let Some(required_previous_middleware) =
             ctx.accounts.middleware_account.previous_middleware
         {
-            match ctx.accounts.transaction_account.approved_middleware {
-                None => err!(CryptidError::IncorrectMiddleware),
-                Some(approved_previous_middleware) => {
-                    require_keys_eq!(
-                        required_previous_middleware,
-                        approved_previous_middleware,
-                        CryptidError::IncorrectMiddleware
-                    );
-                    Ok(())
-                }
-            }?;
+            require!(
+                ctx.accounts
+                    .transaction_account
+                    .is_approved_by(&required_previous_middleware),
+                CryptidError::IncorrectMiddleware
+            );
         }
         ExecuteMiddleware::approve(ctx)
     }
@@ -96,6 +91,9 @@ pub struct ExecuteMiddleware<'info> {
     pub cryptid_account: Account<'info, CryptidAccount>,
     pub signer: Signer<'info>,
     pub cryptid_program: Program<'info, Cryptid>,
+    /// The registry of middleware programs that can approve cryptid transactions
+    /// CHECK: The registry is checked by the cryptid program
+    pub approval_registry: UncheckedAccount<'info>,
 }
 impl<'info> ExecuteMiddleware<'info> {
     // TODO abstract this into shared?
@@ -104,6 +102,7 @@ impl<'info> ExecuteMiddleware<'info> {
         let cpi_accounts = SuperuserApproveExecution {
             middleware_account: ctx.accounts.middleware_account.to_account_info(),
             transaction_account: ctx.accounts.transaction_account.to_account_info(),
+            approval_registry: ctx.accounts.approval_registry.to_account_info(),
             cryptid_account: ctx.accounts.cryptid_account.to_account_info(),
         };
         // define seeds inline here rather than extract to a function